@@ -1,14 +1,14 @@
-use std::env;
+use std::{env, time::Duration};
 
 use crate::{
-    cmds,
-    result::CliResult,
+    cmds::{self, OutputFormat},
+    result::{CliError, CliResult},
     styles::{self, fmt_cyan},
     utils,
 };
-use camino::Utf8PathBuf;
-use clap::{Parser, Subcommand};
-use log::info;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use log::{info, warn};
 
 #[derive(Parser)]
 #[command(name = "sideko")]
@@ -41,6 +41,91 @@ pub struct SidekoCli {
     #[arg(long, short = 'v', action = clap::ArgAction::Count, global = true)]
     verbose: u8,
 
+    /// Format of machine-oriented output, `json`/`yaml` also emit errors in that format on stderr [env: SIDEKO_OUTPUT_FORMAT]
+    #[arg(long, global = true)]
+    output_format: Option<OutputFormat>,
+
+    /// Color palette of styled output, `mono` disables colors (also enabled by `NO_COLOR`) [env: SIDEKO_COLOR_THEME]
+    #[arg(long, global = true)]
+    color_theme: Option<styles::ColorTheme>,
+
+    /// Disable emoji in output (e.g. for terminals or log systems that render them poorly) [env: SIDEKO_NO_EMOJI]
+    #[arg(long, global = true)]
+    no_emoji: bool,
+
+    /// Append a JSON record of each failure to this file (e.g. for CI postmortems) [env: SIDEKO_ERROR_LOG]
+    #[arg(long, global = true)]
+    error_log: Option<Utf8PathBuf>,
+
+    /// Abort interactive prompts that receive no input within this many seconds [env: SIDEKO_PROMPT_TIMEOUT]
+    #[arg(long, global = true, value_name = "SECS")]
+    prompt_timeout: Option<u64>,
+
+    /// Take the prompt's default answer on --prompt-timeout instead of aborting [env: SIDEKO_PROMPT_TIMEOUT_DEFAULT]
+    #[arg(long, global = true, requires = "prompt_timeout")]
+    prompt_timeout_default: bool,
+
+    /// Delay in milliseconds before the first retry of a failed request, doubled per retry [env: SIDEKO_RETRY_BASE_DELAY]
+    #[arg(long, global = true, value_name = "MS")]
+    retry_base_delay: Option<u64>,
+
+    /// Upper bound in milliseconds of the delay between retries [env: SIDEKO_RETRY_MAX_DELAY]
+    #[arg(long, global = true, value_name = "MS")]
+    retry_max_delay: Option<u64>,
+
+    /// Randomize retry delays so parallel jobs do not retry in lockstep (default: true) [env: SIDEKO_RETRY_JITTER]
+    #[arg(
+        long,
+        global = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL"
+    )]
+    retry_jitter: Option<bool>,
+
+    /// Times a transient SDK generate/update failure (connection error, 429, 502, 503, 504)
+    /// is retried (default: 2) [env: SIDEKO_RETRIES]
+    #[arg(long, global = true, value_name = "N")]
+    retries: Option<u32>,
+
+    /// Only retry requests that may change server state (SDK generate/update) when they
+    /// failed before reaching the server, e.g. connection refused [env: SIDEKO_RETRY_IDEMPOTENT_ONLY]
+    #[arg(long, global = true)]
+    retry_idempotent_only: bool,
+
+    /// Fail on duplicate keys in configs/specs and unknown top-level OpenAPI fields
+    /// instead of warning [env: SIDEKO_PARSE_STRICT]
+    #[arg(long, global = true, conflicts_with = "parse_lenient")]
+    parse_strict: bool,
+
+    /// Only warn on duplicate keys and unknown top-level fields (default), overriding
+    /// $SIDEKO_PARSE_STRICT
+    #[arg(long, global = true)]
+    parse_lenient: bool,
+
+    /// Max concurrent requests to the Sideko API across the whole command (default: 4) [env: SIDEKO_MAX_PARALLEL_REQUESTS]
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    max_parallel_requests: Option<u64>,
+
+    /// Run the `git` CLI to check SDK repo status and apply updates instead of the
+    /// built-in libgit2 [env: SIDEKO_USE_GIT_CLI]
+    #[arg(long, global = true)]
+    use_git_cli: bool,
+
+    /// Config profile to use, e.g. separate API keys and base URLs for staging and
+    /// production [env: SIDEKO_PROFILE]
+    #[arg(
+        long,
+        global = true,
+        value_parser = crate::utils::validators::validate_profile_name
+    )]
+    profile: Option<String>,
+
     /// Load config from custom path
     #[arg(
         long,
@@ -51,19 +136,129 @@ pub struct SidekoCli {
 impl SidekoCli {
     async fn handle(&self) -> CliResult<()> {
         // init logger and environment
+        if let Some(color_theme) = &self.color_theme {
+            env::set_var(
+                utils::config::ConfigKey::ColorTheme.to_string(),
+                color_theme
+                    .to_possible_value()
+                    .expect("no skipped variants")
+                    .get_name(),
+            );
+        }
         utils::logging::init_logger(self.quiet, self.verbose);
 
+        if let Some(output_format) = &self.output_format {
+            env::set_var(
+                utils::config::ConfigKey::OutputFormat.to_string(),
+                output_format
+                    .to_possible_value()
+                    .expect("no skipped variants")
+                    .get_name(),
+            );
+        }
+
+        if let Some(error_log) = &self.error_log {
+            env::set_var(utils::config::ConfigKey::ErrorLog.to_string(), error_log);
+        }
+
+        if let Some(prompt_timeout) = &self.prompt_timeout {
+            env::set_var(
+                utils::config::ConfigKey::PromptTimeout.to_string(),
+                prompt_timeout.to_string(),
+            );
+        }
+        if self.prompt_timeout_default {
+            env::set_var(
+                utils::config::ConfigKey::PromptTimeoutDefault.to_string(),
+                "1",
+            );
+        }
+
+        if let Some(retry_base_delay) = &self.retry_base_delay {
+            env::set_var(
+                utils::config::ConfigKey::RetryBaseDelay.to_string(),
+                retry_base_delay.to_string(),
+            );
+        }
+        if let Some(retry_max_delay) = &self.retry_max_delay {
+            env::set_var(
+                utils::config::ConfigKey::RetryMaxDelay.to_string(),
+                retry_max_delay.to_string(),
+            );
+        }
+        if let Some(retry_jitter) = &self.retry_jitter {
+            env::set_var(
+                utils::config::ConfigKey::RetryJitter.to_string(),
+                retry_jitter.to_string(),
+            );
+        }
+        if let Some(retries) = &self.retries {
+            env::set_var(
+                utils::config::ConfigKey::Retries.to_string(),
+                retries.to_string(),
+            );
+        }
+        if self.parse_strict {
+            env::set_var(utils::config::ConfigKey::ParseStrict.to_string(), "1");
+        } else if self.parse_lenient {
+            env::set_var(utils::config::ConfigKey::ParseStrict.to_string(), "0");
+        }
+        if self.retry_idempotent_only {
+            env::set_var(
+                utils::config::ConfigKey::RetryIdempotentOnly.to_string(),
+                "1",
+            );
+        }
+        if self.use_git_cli {
+            env::set_var(utils::config::ConfigKey::UseGitCli.to_string(), "1");
+        }
+        if let Some(max_parallel_requests) = &self.max_parallel_requests {
+            env::set_var(
+                utils::config::ConfigKey::MaxParallelRequests.to_string(),
+                max_parallel_requests.to_string(),
+            );
+        }
+
+        if self.no_emoji {
+            env::set_var(utils::config::ConfigKey::NoEmoji.to_string(), "1");
+        }
+
+        if let Some(profile) = &self.profile {
+            env::set_var(utils::config::ConfigKey::Profile.to_string(), profile);
+        }
         if let Some(cfg_path) = &self.config {
-            env::set_var(utils::config::ConfigKey::ConfigPath.to_string(), cfg_path);
+            utils::config::set_config_path_flag(cfg_path);
+        }
+        // `config edit` must be able to open (and fix) a config that fails to load
+        let editing_config = matches!(
+            &self.command,
+            SidekoCommands::Config(cmds::ConfigSubcommand::Edit(_))
+        );
+        match utils::config::load() {
+            Err(e) if editing_config => warn!("{}", e.message()),
+            res => res?,
         }
-        utils::config::load()?;
 
-        utils::check_for_updates().await?;
+        // doctor reports API reachability as one of its checks, env, show, and edit
+        // are local and must work offline
+        let skip_update_check = editing_config
+            || matches!(
+                &self.command,
+                SidekoCommands::Config(
+                    cmds::ConfigSubcommand::Doctor(_)
+                        | cmds::ConfigSubcommand::Env(_)
+                        | cmds::ConfigSubcommand::Show(_)
+                )
+            );
+        if !skip_update_check {
+            utils::check_for_updates().await?;
+        }
 
         // Run command
         match &self.command {
             SidekoCommands::Login(cmd) => cmd.handle().await,
             SidekoCommands::Logout(cmd) => cmd.handle().await,
+            SidekoCommands::Whoami(cmd) => cmd.handle().await,
             SidekoCommands::Api(cmd) => cmd.handle().await,
             SidekoCommands::Sdk(cmd) => cmd.handle().await,
             SidekoCommands::Doc(cmd) => cmd.handle().await,
@@ -94,20 +289,71 @@ enum SidekoCommands {
     ///
     /// Removes the Sideko API key from the OS-native key service
     /// (e.g. `keychain` on macOS, `keyutils` on Linux, `Windows Credential Manager` on Windows)
+    /// and the config file, `--all` also clears every other stored setting
     Logout(cmds::LogoutCommand),
 
+    /// Show the authenticated organization and CLI connection details
+    ///
+    /// Exits non-zero when the CLI is not authenticated, use `--output-format json` for structured results
+    Whoami(cmds::WhoamiCommand),
+
     /// Configure the CLI
     #[command(subcommand)]
     Config(cmds::ConfigSubcommand),
 }
 
+/// How long an interrupted command gets to unwind before the process exits
+const INTERRUPT_GRACE: Duration = Duration::from_millis(500);
+
 pub async fn cli(args: Vec<String>) -> CliResult<()> {
-    let cli = SidekoCli::parse_from(args);
+    let matches = SidekoCli::command().get_matches_from(args);
+    // subcommand path only (e.g. `sdk create`), flag values may contain secrets
+    let mut subcommands = vec![];
+    let mut subcommand = matches.subcommand();
+    while let Some((name, sub_matches)) = subcommand {
+        subcommands.push(name);
+        subcommand = sub_matches.subcommand();
+    }
+    let command = subcommands.join(" ");
+    let cli = SidekoCli::from_arg_matches(&matches)
+        .unwrap_or_else(|e| e.format(&mut SidekoCli::command()).exit());
+
+    // blocking sections of a command (unpacking, git subprocesses, prompts) never yield
+    // to the select below, so this listener runs on a runtime worker and exits the
+    // process if the command has not unwound shortly after Ctrl-C
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            tokio::time::sleep(INTERRUPT_GRACE).await;
+            utils::interrupt::cleanup();
+            CliError::Interrupted.log();
+            std::process::exit(CliError::Interrupted.exit_code());
+        }
+    });
 
-    let handled = cli.handle().await;
+    // on Ctrl-C partial outputs are removed, then the command future is dropped
+    // with its temp dirs and spinners
+    let handled = tokio::select! {
+        res = utils::throttle::flow(cli.handle()) => res,
+        _ = tokio::signal::ctrl_c() => {
+            utils::interrupt::cleanup();
+            Err(CliError::Interrupted)
+        }
+    };
     if let Err(e) = &handled {
-        e.log();
-        info!("Re-run the command in verbose mode (-v/-vv) to for more information")
+        match OutputFormat::current() {
+            OutputFormat::Json => e.log_json(),
+            OutputFormat::Yaml => e.log_yaml(),
+            OutputFormat::Text => {
+                e.log();
+                info!("Re-run the command in verbose mode (-v/-vv) to for more information")
+            }
+        }
+
+        if let Some(error_log) = utils::config::ConfigKey::ErrorLog.get_env() {
+            if let Err(log_err) = e.append_to_log(Utf8Path::new(&error_log), &command) {
+                warn!("Failed appending error to error log {error_log}: {log_err}");
+            }
+        }
     }
 
     handled
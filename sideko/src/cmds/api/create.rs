@@ -1,6 +1,5 @@
 use camino::Utf8PathBuf;
 use sideko_rest_api::{models::VersionOrBump, resources::api::InitRequest, UploadFile};
-use tabled::settings::{object::Rows, Color};
 
 use crate::{
     cmds::DisplayOutput,
@@ -37,7 +36,7 @@ pub struct ApiCreateCommand {
 }
 impl ApiCreateCommand {
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
         let version = client
             .api()
             .init(InitRequest {
@@ -61,9 +60,10 @@ impl ApiCreateCommand {
                 let mut api_table = tabled::Table::new([TabledApi {
                     api: version.api.clone(),
                     subdomain: org.subdomain.clone(),
+                    timestamps: Default::default(),
                 }]);
                 utils::tabled::header_panel(&mut api_table, "API");
-                api_table.modify(Rows::single(1), Color::BOLD);
+                utils::tabled::bold_row(&mut api_table, 1);
                 utils::logging::log_table(api_table);
 
                 let mut version_table = tabled::Table::new([TabledApiSpec {
@@ -71,7 +71,7 @@ impl ApiCreateCommand {
                     org_subdomain: org.subdomain.clone(),
                 }]);
                 utils::tabled::header_panel(&mut version_table, "Initial Version");
-                version_table.modify(Rows::single(1), Color::BOLD);
+                utils::tabled::bold_row(&mut version_table, 1);
                 utils::logging::log_table(version_table);
             }
         }
@@ -0,0 +1,44 @@
+use crate::{
+    cmds::DisplayOutput,
+    result::CliResult,
+    utils::{self, get_sideko_client, time::TimestampArgs},
+};
+
+use super::tabled::TabledApi;
+
+#[derive(clap::Args)]
+pub struct ApiGetCommand {
+    /// API name or id e.g. my-api
+    #[arg(long)]
+    pub api: String,
+
+    /// Display result as a raw json or prettified
+    #[arg(long, default_value = "pretty")]
+    pub display: DisplayOutput,
+
+    #[command(flatten)]
+    pub timestamps: TimestampArgs,
+}
+impl ApiGetCommand {
+    pub async fn handle(&self) -> CliResult<()> {
+        let api = utils::resolve::resolve_api(&self.api).await?;
+
+        match &self.display {
+            DisplayOutput::Raw => utils::logging::log_json_raw(&api),
+            DisplayOutput::Pretty => {
+                let org = get_sideko_client().await?.org().get().await?;
+
+                let mut table = tabled::Table::new([TabledApi {
+                    api,
+                    subdomain: org.subdomain,
+                    timestamps: self.timestamps.clone(),
+                }]);
+                utils::tabled::header_panel(&mut table, "API");
+                utils::tabled::bold_row(&mut table, 1);
+                utils::logging::log_table(table);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,7 @@
-use tabled::settings::{object::Rows, Color};
-
 use crate::{
     cmds::DisplayOutput,
     result::CliResult,
-    utils::{self, get_sideko_client},
+    utils::{self, get_sideko_client, time::TimestampArgs},
 };
 
 use super::tabled::TabledApi;
@@ -13,10 +11,13 @@ pub struct ApiListCommand {
     /// Display result as a raw json or prettified
     #[arg(long, default_value = "pretty")]
     pub display: DisplayOutput,
+
+    #[command(flatten)]
+    pub timestamps: TimestampArgs,
 }
 impl ApiListCommand {
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
         let apis = client.api().list().await?;
 
         match &self.display {
@@ -27,9 +28,10 @@ impl ApiListCommand {
                 let mut table = tabled::Table::new(apis.into_iter().map(|api| TabledApi {
                     api,
                     subdomain: org.subdomain.clone(),
+                    timestamps: self.timestamps.clone(),
                 }));
                 utils::tabled::header_panel(&mut table, "APIs");
-                table.modify(Rows::single(1), Color::BOLD);
+                utils::tabled::bold_row(&mut table, 1);
                 utils::logging::log_table(table);
             }
         }
@@ -1,6 +1,7 @@
 use crate::result::CliResult;
 
 mod create;
+mod get;
 mod list;
 mod stats;
 mod tabled;
@@ -16,6 +17,8 @@ pub enum ApiSubcommand {
     // ------------ COMMANDS ------------
     /// Create a new API
     Create(create::ApiCreateCommand),
+    /// Show an API by name or id
+    Get(get::ApiGetCommand),
     /// List all APIs
     List(list::ApiListCommand),
     /// Display stats gathered from the API specification
@@ -27,6 +30,7 @@ impl ApiSubcommand {
         match self {
             ApiSubcommand::Version(cmd) => cmd.handle().await,
             ApiSubcommand::Create(cmd) => cmd.handle().await,
+            ApiSubcommand::Get(cmd) => cmd.handle().await,
             ApiSubcommand::List(cmd) => cmd.handle().await,
             ApiSubcommand::Stats(cmd) => cmd.handle().await,
         }
@@ -23,7 +23,7 @@ pub struct ApiStatsCommand {
 }
 impl ApiStatsCommand {
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
         let stats = client
             .api()
             .spec()
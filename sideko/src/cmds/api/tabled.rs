@@ -1,23 +1,33 @@
 use sideko_rest_api::models::Api;
 
-use crate::utils::url_builder::ApiUrl;
+use crate::{
+    styles::with_emoji,
+    utils::{time::TimestampArgs, url_builder::ApiUrl},
+};
 
 pub struct TabledApi {
     pub api: Api,
     pub subdomain: String,
+    pub timestamps: TimestampArgs,
 }
 impl tabled::Tabled for TabledApi {
-    const LENGTH: usize = 3;
+    const LENGTH: usize = 4;
 
     fn fields(&self) -> Vec<std::borrow::Cow<'_, str>> {
         vec![
             self.api.name.as_str().into(),
             self.api.version_count.to_string().into(),
+            self.timestamps.format(&self.api.created_at).into(),
             ApiUrl::new(&self.api.name).build(&self.subdomain).into(),
         ]
     }
 
     fn headers() -> Vec<std::borrow::Cow<'static, str>> {
-        vec!["Name".into(), "Versions".into(), "🔗 Link".into()]
+        vec![
+            "Name".into(),
+            "Versions".into(),
+            "Created".into(),
+            with_emoji("🔗", "Link").into(),
+        ]
     }
 }
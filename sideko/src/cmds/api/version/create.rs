@@ -1,6 +1,5 @@
 use camino::Utf8PathBuf;
 use sideko_rest_api::{models::VersionOrBump, resources::api::spec::CreateRequest, UploadFile};
-use tabled::settings::{object::Rows, Color};
 
 use crate::{
     cmds::DisplayOutput,
@@ -37,7 +36,7 @@ pub struct ApiVersionCreateCommand {
 }
 impl ApiVersionCreateCommand {
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
         let new_version = client
             .api()
             .spec()
@@ -64,7 +63,7 @@ impl ApiVersionCreateCommand {
                     org_subdomain: org.subdomain.clone(),
                 }]);
                 utils::tabled::header_panel(&mut table, "New API Version");
-                table.modify(Rows::single(1), Color::BOLD);
+                utils::tabled::bold_row(&mut table, 1);
 
                 utils::logging::log_table(table);
             }
@@ -1,5 +1,4 @@
 use sideko_rest_api::resources::api::spec::ListRequest;
-use tabled::settings::{object::Rows, Color};
 
 use crate::{
     cmds::DisplayOutput,
@@ -25,7 +24,7 @@ pub struct ApiVersionListCommand {
 }
 impl ApiVersionListCommand {
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
         let mut versions = client
             .api()
             .spec()
@@ -48,7 +47,7 @@ impl ApiVersionListCommand {
                 });
                 let mut table = tabled::Table::new(iter);
                 utils::tabled::header_panel(&mut table, "API Versions");
-                table.modify(Rows::single(1), Color::BOLD);
+                utils::tabled::bold_row(&mut table, 1);
 
                 utils::logging::log_table(table);
             }
@@ -1,6 +1,9 @@
 use sideko_rest_api::models::ApiSpec;
 
-use crate::utils::url_builder::ApiUrl;
+use crate::{
+    styles::{emoji_or, with_emoji},
+    utils::url_builder::ApiUrl,
+};
 
 pub struct TabledApiSpec {
     pub version: ApiSpec,
@@ -11,9 +14,9 @@ impl tabled::Tabled for TabledApiSpec {
 
     fn fields(&self) -> Vec<std::borrow::Cow<'_, str>> {
         let mock_enabled = if self.version.mock_server.enabled {
-            "🟢"
+            emoji_or("🟢", "on")
         } else {
-            "🔴"
+            emoji_or("🔴", "off")
         };
         vec![
             self.version.version.as_str().into(),
@@ -31,7 +34,7 @@ impl tabled::Tabled for TabledApiSpec {
             "Version".into(),
             "API".into(),
             "Mock Server".into(),
-            "🔗 Link".into(),
+            with_emoji("🔗", "Link").into(),
         ]
     }
 }
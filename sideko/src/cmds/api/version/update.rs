@@ -1,6 +1,5 @@
 use camino::Utf8PathBuf;
 use sideko_rest_api::{models::ApiVersion, resources::api::spec::PatchRequest, UploadFile};
-use tabled::settings::{object::Rows, Color};
 
 use crate::{
     cmds::DisplayOutput,
@@ -41,7 +40,7 @@ pub struct ApiVersionUpdateCommand {
 }
 impl ApiVersionUpdateCommand {
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
         let openapi = if let Some(path) = &self.spec {
             Some(UploadFile::from_path(path.as_str()).map_err(|e| {
                 CliError::io_custom(format!("Failed reading OpenAPI from path: {path}"), e)
@@ -72,7 +71,7 @@ impl ApiVersionUpdateCommand {
                     org_subdomain: org.subdomain.clone(),
                 }]);
                 utils::tabled::header_panel(&mut table, "Updated API Version");
-                table.modify(Rows::single(1), Color::BOLD);
+                utils::tabled::bold_row(&mut table, 1);
 
                 utils::logging::log_table(table);
             }
@@ -2,6 +2,7 @@ use crate::{
     cli::SidekoCli,
     result::{CliError, CliResult},
     styles::fmt_green,
+    utils::prompt,
 };
 use clap::{Args, CommandFactory};
 use clap_complete::{generate, Shell};
@@ -36,13 +37,10 @@ impl AutocompleteCommand {
             rc_path.display()
         );
 
-        let confirm = Confirm::new(&message)
-            .with_default(true)
-            .prompt()
-            .map_err(|err| CliError::Inquire {
-                err,
-                override_msg: None,
-            })?;
+        let confirm = prompt::prompt(
+            move || Confirm::new(&message).with_default(true).prompt(),
+            Some(true),
+        )?;
 
         if !confirm {
             return Ok(());
@@ -124,16 +122,11 @@ impl AutocompleteCommand {
     fn update_rc_file(&self, rc_path: &PathBuf, completion_path: &Path) -> CliResult<()> {
         // Create RC file if it doesn't exist
         if !rc_path.exists() {
-            let confirm = Confirm::new(&format!(
-                "RC file {} does not exist. Create it?",
-                rc_path.display()
-            ))
-            .with_default(true)
-            .prompt()
-            .map_err(|err| CliError::Inquire {
-                err,
-                override_msg: None,
-            })?;
+            let message = format!("RC file {} does not exist. Create it?", rc_path.display());
+            let confirm = prompt::prompt(
+                move || Confirm::new(&message).with_default(true).prompt(),
+                Some(true),
+            )?;
 
             if !confirm {
                 return Ok(());
@@ -0,0 +1,363 @@
+use camino::Utf8PathBuf;
+use inquire::Confirm;
+use log::{info, warn};
+use sideko_rest_api::{resources::cli::CheckUpdatesRequest, SidekoClient};
+
+use crate::{
+    cmds::{sdk::repo, OutputFormat},
+    result::{CliError, CliResult},
+    styles::{fmt_green, fmt_red, fmt_yellow},
+    utils::{self, config::ConfigKey, prompt, sideko_client_with_base_url, throttle},
+};
+
+#[derive(clap::Args)]
+pub(crate) struct DoctorCommand {
+    /// Repair detected problems, confirming each fix before it is applied
+    #[arg(long)]
+    fix: bool,
+
+    /// Apply fixes without confirmation
+    #[arg(long, requires = "fix")]
+    yes: bool,
+
+    /// Fix to leave untouched (repeatable)
+    #[arg(long, value_name = "FIX", requires = "fix")]
+    skip: Vec<DoctorFix>,
+}
+
+/// Repairs `config doctor --fix` can apply
+#[derive(clap::ValueEnum, Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum DoctorFix {
+    /// Create the missing directory of the config file
+    ConfigDir,
+    /// Append `/v1` to a configured base URL missing it
+    BaseUrl,
+    /// Move a plaintext API key from the config file into the keyring
+    KeyringKey,
+    /// Remove a stray `sdk_update.patch` left in the current directory
+    StrayPatch,
+}
+impl DoctorFix {
+    fn name(&self) -> &'static str {
+        match self {
+            DoctorFix::ConfigDir => "config-dir",
+            DoctorFix::BaseUrl => "base-url",
+            DoctorFix::KeyringKey => "keyring-key",
+            DoctorFix::StrayPatch => "stray-patch",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum FixStatus {
+    Applied,
+    Skipped,
+    Failed,
+}
+
+/// A detected problem and what the fix did about it
+#[derive(Debug, serde::Serialize)]
+struct FixAction {
+    fix: DoctorFix,
+    status: FixStatus,
+    detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct DoctorCheck {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+}
+impl DoctorCheck {
+    fn new<D: ToString>(name: &'static str, status: CheckStatus, detail: D) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct DoctorReport {
+    healthy: bool,
+    checks: Vec<DoctorCheck>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<FixAction>,
+}
+
+impl DoctorCommand {
+    /// Problems `--fix` can repair, each with a description of the repair
+    fn detect_fixes(&self) -> CliResult<Vec<(DoctorFix, String)>> {
+        let mut fixes = vec![];
+
+        let config_path = utils::config::get_config_path()?;
+        if let Some(dir) = config_path.parent().filter(|d| !d.as_str().is_empty()) {
+            if !dir.exists() {
+                fixes.push((
+                    DoctorFix::ConfigDir,
+                    format!("Create config directory {dir}"),
+                ));
+            }
+        }
+
+        if let Some(base_url) = ConfigKey::ApiBaseUrl.get_dotenv()? {
+            if !base_url.trim_end_matches('/').ends_with("/v1") {
+                fixes.push((
+                    DoctorFix::BaseUrl,
+                    format!("Set base URL to {}", normalize_base_url(&base_url)),
+                ));
+            }
+        }
+
+        if ConfigKey::ApiKey.get_dotenv()?.is_some() {
+            fixes.push((
+                DoctorFix::KeyringKey,
+                format!("Move plaintext API key from {config_path} to the keyring"),
+            ));
+        }
+
+        let stray_patch = Utf8PathBuf::from(repo::PATCH_FILENAME);
+        if stray_patch.is_file() {
+            fixes.push((
+                DoctorFix::StrayPatch,
+                format!("Remove stray {stray_patch} from the current directory"),
+            ));
+        }
+
+        Ok(fixes)
+    }
+
+    fn apply_fix(&self, fix: &DoctorFix) -> CliResult<()> {
+        match fix {
+            DoctorFix::ConfigDir => {
+                let config_path = utils::config::get_config_path()?;
+                if let Some(dir) = config_path.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| {
+                        CliError::io_custom(format!("Failed creating config directory {dir}"), e)
+                    })?;
+                }
+            }
+            DoctorFix::BaseUrl => {
+                if let Some(base_url) = ConfigKey::ApiBaseUrl.get_dotenv()? {
+                    let normalized = normalize_base_url(&base_url);
+                    ConfigKey::ApiBaseUrl.set_env(&normalized)?;
+                    std::env::set_var(ConfigKey::ApiBaseUrl.to_string(), normalized);
+                }
+            }
+            DoctorFix::KeyringKey => {
+                if let Some(key) = ConfigKey::ApiKey.get_dotenv()? {
+                    ConfigKey::ApiKey.set_keyring(key)?;
+                    ConfigKey::ApiKey.unset_env()?;
+                    // the key was loaded into the environment from the config file
+                    std::env::remove_var(ConfigKey::ApiKey.to_string());
+                }
+            }
+            DoctorFix::StrayPatch => {
+                std::fs::remove_file(repo::PATCH_FILENAME).map_err(|e| {
+                    CliError::io_custom(format!("Failed removing {}", repo::PATCH_FILENAME), e)
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies detected fixes, confirming each unless `--yes` is set
+    fn run_fixes(&self) -> CliResult<Vec<FixAction>> {
+        let mut actions = vec![];
+        for (fix, detail) in self.detect_fixes()? {
+            let status = if self.skip.contains(&fix) {
+                FixStatus::Skipped
+            } else {
+                let confirmed = self.yes || {
+                    let message = format!("{detail}?");
+                    prompt::prompt(
+                        move || Confirm::new(&message).with_default(true).prompt(),
+                        Some(true),
+                    )?
+                };
+                if !confirmed {
+                    FixStatus::Skipped
+                } else if let Err(e) = self.apply_fix(&fix) {
+                    warn!("Fix {} failed: {}", fix.name(), e.message());
+                    FixStatus::Failed
+                } else {
+                    FixStatus::Applied
+                }
+            };
+            actions.push(FixAction {
+                fix,
+                status,
+                detail,
+            });
+        }
+
+        Ok(actions)
+    }
+
+    async fn run_checks(&self) -> Vec<DoctorCheck> {
+        let mut checks = vec![];
+
+        checks.push(match utils::config::get_config_path() {
+            Ok(path) if path.exists() => {
+                DoctorCheck::new("config_file", CheckStatus::Pass, format!("Loaded {path}"))
+            }
+            Ok(path) => DoctorCheck::new(
+                "config_file",
+                CheckStatus::Pass,
+                format!("{path} not present, using defaults"),
+            ),
+            Err(e) => DoctorCheck::new("config_file", CheckStatus::Fail, e.message()),
+        });
+
+        let base_url = match utils::config::get_base_url() {
+            Ok(base_url) => {
+                checks.push(if base_url.ends_with("/v1") {
+                    DoctorCheck::new("base_url", CheckStatus::Pass, &base_url)
+                } else {
+                    DoctorCheck::new(
+                        "base_url",
+                        CheckStatus::Warn,
+                        format!("{base_url} does not end with `/v1`"),
+                    )
+                });
+                Some(base_url)
+            }
+            Err(e) => {
+                checks.push(DoctorCheck::new("base_url", CheckStatus::Fail, e.message()));
+                None
+            }
+        };
+
+        // the API checks need a usable base url
+        if let Some(base_url) = &base_url {
+            let mut client = SidekoClient::default().with_base_url(base_url);
+            let _permit = throttle::acquire().await;
+            checks.push(
+                match client
+                    .cli()
+                    .check_updates(CheckUpdatesRequest {
+                        cli_version: env!("CARGO_PKG_VERSION").to_string(),
+                    })
+                    .await
+                {
+                    Ok(_) => {
+                        DoctorCheck::new("api_reachable", CheckStatus::Pass, "Sideko API reachable")
+                    }
+                    Err(e) => DoctorCheck::new(
+                        "api_reachable",
+                        CheckStatus::Fail,
+                        CliError::from(e).message(),
+                    ),
+                },
+            );
+        }
+
+        let api_key_source = utils::config::get_api_key_with_source().map(|(_, source)| source);
+        checks.push(match api_key_source {
+            Some(source) => DoctorCheck::new(
+                "api_key",
+                CheckStatus::Pass,
+                format!("API key found in {source}"),
+            ),
+            None => DoctorCheck::new(
+                "api_key",
+                CheckStatus::Fail,
+                "No API key found, run `sideko login`",
+            ),
+        });
+        if let Some(conflict) = utils::config::api_key_keyring_conflict() {
+            checks.push(DoctorCheck::new(
+                "api_key_conflict",
+                CheckStatus::Warn,
+                conflict,
+            ));
+        }
+
+        if let (Some(base_url), Some(_)) = (&base_url, api_key_source) {
+            let mut client = sideko_client_with_base_url(base_url);
+            let _permit = throttle::acquire().await;
+            checks.push(match client.org().get().await {
+                Ok(org) => DoctorCheck::new(
+                    "authentication",
+                    CheckStatus::Pass,
+                    format!("Authenticated to organization `{}`", org.subdomain),
+                ),
+                Err(e) => DoctorCheck::new(
+                    "authentication",
+                    CheckStatus::Fail,
+                    CliError::from(e).message(),
+                ),
+            });
+        }
+
+        checks
+    }
+
+    pub async fn handle(&self) -> CliResult<()> {
+        // fixes run first so the checks report the repaired environment
+        let fixes = if self.fix { self.run_fixes()? } else { vec![] };
+        let checks = self.run_checks().await;
+        let report = DoctorReport {
+            healthy: checks.iter().all(|c| c.status != CheckStatus::Fail),
+            checks,
+            fixes,
+        };
+
+        match OutputFormat::current() {
+            OutputFormat::Json => utils::logging::print_json(&report),
+            OutputFormat::Yaml => utils::logging::print_yaml(&report),
+            OutputFormat::Text => {
+                for action in &report.fixes {
+                    let symbol = match action.status {
+                        FixStatus::Applied => fmt_green("✔"),
+                        FixStatus::Skipped => fmt_yellow("ø"),
+                        FixStatus::Failed => fmt_red("✘"),
+                    };
+                    info!("{symbol} fix {}: {}", action.fix.name(), action.detail);
+                }
+                if self.fix && report.fixes.is_empty() {
+                    info!("Nothing to fix");
+                }
+                for check in &report.checks {
+                    let symbol = match check.status {
+                        CheckStatus::Pass => fmt_green("✔"),
+                        CheckStatus::Warn => fmt_yellow("ø"),
+                        CheckStatus::Fail => fmt_red("✘"),
+                    };
+                    info!("{symbol} {}: {}", check.name, check.detail);
+                }
+            }
+        }
+
+        if report.healthy {
+            Ok(())
+        } else {
+            let failed = report
+                .checks
+                .iter()
+                .filter(|c| c.status == CheckStatus::Fail)
+                .count();
+            Err(CliError::general(format!(
+                "CLI environment is not healthy, {failed} check(s) failed"
+            )))
+        }
+    }
+}
+
+/// Appends `/v1` to a base URL missing the API version
+fn normalize_base_url(url: &str) -> String {
+    format!("{}/v1", url.trim_end_matches('/'))
+}
@@ -0,0 +1,60 @@
+use std::{fs, process};
+
+use log::{debug, info, warn};
+
+use crate::{
+    result::{CliError, CliResult},
+    styles::fmt_green,
+    utils,
+};
+
+#[derive(clap::Args)]
+pub(crate) struct ConfigEditCommand {}
+
+impl ConfigEditCommand {
+    pub async fn handle(&self) -> CliResult<()> {
+        let cfg_path = utils::config::get_config_path()?;
+        if !cfg_path.exists() {
+            utils::config::create_config_dir(&cfg_path)?;
+            fs::write(&cfg_path, "").map_err(|e| {
+                CliError::io_custom(format!("Failed creating sideko config: {cfg_path}"), e)
+            })?;
+            debug!("Created empty config {cfg_path}");
+        }
+
+        if !utils::prompt::terminal_available() {
+            return Err(CliError::general(
+                "An earlier prompt timed out and still holds the terminal, cannot open an editor",
+            ));
+        }
+
+        // editors are commonly configured with arguments (e.g. `code --wait`)
+        let editor = utils::editor::get_editor();
+        let mut args = editor.split_whitespace();
+        let program = args.next().unwrap_or_default();
+        debug!("Opening {cfg_path} with `{editor}`");
+        let status = process::Command::new(program)
+            .args(args)
+            .arg(&cfg_path)
+            .status()
+            .map_err(|e| CliError::io_custom(format!("Failed to run editor `{editor}`"), e))?;
+        if !status.success() {
+            return Err(CliError::general(format!(
+                "Editor `{editor}` exited with {status}, config changes may not have been saved"
+            )));
+        }
+
+        utils::config::load()?;
+        let unrecognized = utils::config::unrecognized_entries(&cfg_path)?;
+        for entry in &unrecognized {
+            warn!("Unrecognized config entry {entry}, expected a `KEY=value` sideko config key");
+        }
+        if unrecognized.is_empty() {
+            info!("{} Config saved: {cfg_path}", fmt_green("✔"));
+        } else {
+            info!("Run `sideko config show` to list the recognized config keys");
+        }
+
+        Ok(())
+    }
+}
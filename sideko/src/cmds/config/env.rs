@@ -0,0 +1,110 @@
+use camino::Utf8PathBuf;
+use log::info;
+use tabled::settings::{object::Rows, Remove};
+
+use crate::{
+    cmds::OutputFormat,
+    result::CliResult,
+    utils::{self, config::ValueSource},
+};
+
+#[derive(clap::Args)]
+pub(crate) struct ConfigEnvCommand {}
+
+#[derive(Debug, serde::Serialize)]
+struct ApiKeySummary {
+    source: &'static str,
+    masked: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct EnvSummary {
+    /// `None` for the default profile
+    profile: Option<String>,
+    base_url: String,
+    base_url_source: ValueSource,
+    config_path: Utf8PathBuf,
+    config_path_source: ValueSource,
+    config_exists: bool,
+    api_key: Option<ApiKeySummary>,
+    keyring_backend: &'static str,
+    cli_version: &'static str,
+}
+
+impl ConfigEnvCommand {
+    pub async fn handle(&self) -> CliResult<()> {
+        let config_path = utils::config::get_config_path()?;
+        let summary = EnvSummary {
+            profile: utils::config::active_profile(),
+            base_url: utils::config::get_base_url()?,
+            base_url_source: utils::config::get_base_url_source(),
+            config_exists: config_path.exists(),
+            config_path,
+            config_path_source: utils::config::get_config_path_source(),
+            api_key: utils::config::get_api_key_with_source().map(|(key, source)| ApiKeySummary {
+                source,
+                masked: utils::config::mask_api_key(&key),
+            }),
+            keyring_backend: utils::config::keyring_backend(),
+            cli_version: env!("CARGO_PKG_VERSION"),
+        };
+
+        match OutputFormat::current() {
+            OutputFormat::Json => utils::logging::print_json(&summary),
+            OutputFormat::Yaml => utils::logging::print_yaml(&summary),
+            OutputFormat::Text => {
+                let rows = vec![
+                    EnvRow::new("CLI version", summary.cli_version),
+                    EnvRow::new("Profile", summary.profile.as_deref().unwrap_or("default")),
+                    EnvRow::new(
+                        "Base URL",
+                        format!("{} ({})", summary.base_url, summary.base_url_source),
+                    ),
+                    EnvRow::new(
+                        "Config file",
+                        format!(
+                            "{} ({}{})",
+                            summary.config_path,
+                            summary.config_path_source,
+                            if summary.config_exists {
+                                ""
+                            } else {
+                                ", missing"
+                            }
+                        ),
+                    ),
+                    EnvRow::new(
+                        "API key",
+                        summary
+                            .api_key
+                            .as_ref()
+                            .map(|k| format!("{} ({})", k.masked, k.source))
+                            .unwrap_or_else(|| "not set".into()),
+                    ),
+                    EnvRow::new("Keyring backend", summary.keyring_backend),
+                ];
+                let mut table = tabled::Table::new(rows);
+                table.with(Remove::row(Rows::first()));
+                utils::tabled::header_panel(&mut table, "Sideko CLI Environment");
+                utils::logging::log_table(table);
+                info!("Paste this when contacting support, the API key is masked");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(tabled::Tabled)]
+struct EnvRow {
+    name: String,
+    val: String,
+}
+impl EnvRow {
+    pub fn new<N: ToString, V: ToString>(name: N, val: V) -> Self {
+        Self {
+            name: name.to_string(),
+            val: val.to_string(),
+        }
+    }
+}
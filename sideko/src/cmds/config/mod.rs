@@ -1,6 +1,12 @@
 use crate::result::CliResult;
 
 mod autocomplete;
+mod doctor;
+mod edit;
+mod env;
+mod path;
+mod set_default_lang;
+mod show;
 
 #[derive(clap::Subcommand)]
 pub enum ConfigSubcommand {
@@ -11,12 +17,46 @@ pub enum ConfigSubcommand {
     ///
     /// Then add `source ~/sideko-complete.sh` to `~/.zshrc`
     Autocomplete(autocomplete::AutocompleteCommand),
+
+    /// Check the CLI environment is ready to use (config, API reachability, authentication)
+    ///
+    /// Exits non-zero when any check fails, use `--output-format json` for structured results.
+    /// With `--fix` detected problems (missing config directory, base URL without `/v1`,
+    /// plaintext API key in the config file, stray `sdk_update.patch`) are repaired first
+    Doctor(doctor::DoctorCommand),
+
+    /// Open the config file in your editor (`$GIT_EDITOR`, `$VISUAL`, or `$EDITOR`),
+    /// creating it if it does not exist
+    ///
+    /// The config is reloaded after the editor exits, warning about entries that
+    /// are not recognized config keys
+    Edit(edit::ConfigEditCommand),
+
+    /// Print a summary of the effective CLI environment (base URL, config file, API key, keyring)
+    /// for troubleshooting, the API key is masked
+    Env(env::ConfigEnvCommand),
+
+    /// Print the resolved config file location and where it was resolved from
+    Path(path::ConfigPathCommand),
+
+    /// Store the language `sdk create` generates when `--lang` is omitted
+    SetDefaultLang(set_default_lang::ConfigSetDefaultLangCommand),
+
+    /// Print every config key with its resolved value and where it came from
+    /// (flag, env, config file, keyring, or default), the API key is masked
+    Show(show::ConfigShowCommand),
 }
 
 impl ConfigSubcommand {
     pub async fn handle(&self) -> CliResult<()> {
         match self {
             ConfigSubcommand::Autocomplete(cmd) => cmd.handle().await,
+            ConfigSubcommand::Doctor(cmd) => cmd.handle().await,
+            ConfigSubcommand::Edit(cmd) => cmd.handle().await,
+            ConfigSubcommand::Env(cmd) => cmd.handle().await,
+            ConfigSubcommand::Path(cmd) => cmd.handle().await,
+            ConfigSubcommand::SetDefaultLang(cmd) => cmd.handle().await,
+            ConfigSubcommand::Show(cmd) => cmd.handle().await,
         }
     }
 }
@@ -0,0 +1,47 @@
+use log::info;
+
+use crate::{
+    cmds::OutputFormat,
+    result::CliResult,
+    utils::{self, config::ConfigKey},
+};
+
+#[derive(clap::Args)]
+pub(crate) struct ConfigPathCommand {}
+
+impl ConfigPathCommand {
+    pub async fn handle(&self) -> CliResult<()> {
+        let path = utils::config::get_config_path()?;
+        let source = utils::config::get_config_path_source();
+        let exists = path.exists();
+
+        let summary = serde_json::json!({
+            "path": path,
+            "source": source,
+            "exists": exists,
+        });
+        match OutputFormat::current() {
+            OutputFormat::Json => utils::logging::print_json(&summary),
+            OutputFormat::Yaml => utils::logging::print_yaml(&summary),
+            OutputFormat::Text => {
+                info!("{path}");
+                let source_detail = match source {
+                    utils::config::ValueSource::Flag => "--config flag".to_string(),
+                    utils::config::ValueSource::Env => format!("${}", ConfigKey::ConfigPath),
+                    utils::config::ValueSource::Default => "default".to_string(),
+                };
+                info!("Source: {source_detail}");
+                info!(
+                    "Exists: {}",
+                    if exists {
+                        "yes"
+                    } else {
+                        "no (defaults are used)"
+                    }
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use clap::ValueEnum;
+use log::info;
+
+use crate::{cmds::sdk::SdkLang, result::CliResult, styles::fmt_green, utils::config::ConfigKey};
+
+#[derive(clap::Args)]
+pub(crate) struct ConfigSetDefaultLangCommand {
+    /// Language generated by `sideko sdk create` when `--lang` is omitted
+    pub lang: SdkLang,
+}
+
+impl ConfigSetDefaultLangCommand {
+    pub async fn handle(&self) -> CliResult<()> {
+        // stored as the value name so it parses back through `SdkLang`
+        let lang = self
+            .lang
+            .to_possible_value()
+            .expect("every sdk language has a value name");
+        ConfigKey::DefaultLang.set_env(lang.get_name())?;
+        info!(
+            "{} Default SDK language set to {}",
+            fmt_green("✔"),
+            lang.get_name()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use log::{debug, info, warn};
+use tabled::settings::{object::Rows, Remove};
+
+use crate::{
+    cmds::OutputFormat,
+    result::CliResult,
+    utils::{
+        self,
+        config::{ConfigKey, ValueSource},
+    },
+};
+
+#[derive(clap::Args)]
+pub(crate) struct ConfigShowCommand {}
+
+/// Where a config key's resolved value came from
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum KeySource {
+    Flag,
+    Env,
+    ConfigFile,
+    Keyring,
+    Default,
+    Unset,
+}
+impl std::fmt::Display for KeySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self {
+            KeySource::Flag => "flag",
+            KeySource::Env => "env",
+            KeySource::ConfigFile => "config file",
+            KeySource::Keyring => "keyring",
+            KeySource::Default => "default",
+            KeySource::Unset => "not set",
+        };
+
+        write!(f, "{source}")
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ResolvedKey {
+    key: String,
+    value: Option<String>,
+    source: KeySource,
+}
+
+impl ConfigShowCommand {
+    /// Resolves the key like the CLI does: environment (which config file values
+    /// are loaded into), then keyring for the API key, then the built-in default
+    fn resolve(key: &ConfigKey) -> CliResult<ResolvedKey> {
+        let (value, source) = match key {
+            ConfigKey::ConfigPath => {
+                let source = match utils::config::get_config_path_source() {
+                    ValueSource::Flag => KeySource::Flag,
+                    ValueSource::Env => KeySource::Env,
+                    ValueSource::Default => KeySource::Default,
+                };
+                (Some(utils::config::get_config_path()?.to_string()), source)
+            }
+            _ => match key.get_env() {
+                // values loaded from the config file are indistinguishable from the
+                // environment, unless they match what the file holds
+                Some(val) if key.get_dotenv()?.as_ref() == Some(&val) => {
+                    (Some(val), KeySource::ConfigFile)
+                }
+                Some(val) => (Some(val), KeySource::Env),
+                None => match key {
+                    ConfigKey::ApiKey => match key.get_keyring() {
+                        Some(val) => (Some(val), KeySource::Keyring),
+                        None => (None, KeySource::Unset),
+                    },
+                    ConfigKey::ApiBaseUrl => {
+                        (Some(utils::config::get_base_url()?), KeySource::Default)
+                    }
+                    _ => (None, KeySource::Unset),
+                },
+            },
+        };
+
+        let value = match key {
+            ConfigKey::ApiKey => value.map(|v| utils::config::mask_api_key(&v)),
+            _ => value,
+        };
+        Ok(ResolvedKey {
+            key: key.to_string(),
+            value,
+            source,
+        })
+    }
+
+    pub async fn handle(&self) -> CliResult<()> {
+        let resolved = ConfigKey::ALL
+            .iter()
+            .map(Self::resolve)
+            .collect::<CliResult<Vec<_>>>()?;
+        debug!("Resolved {} config keys", resolved.len());
+        if let Some(conflict) = utils::config::api_key_keyring_conflict() {
+            warn!("{conflict}");
+        }
+
+        match OutputFormat::current() {
+            OutputFormat::Json => utils::logging::print_json(&resolved),
+            OutputFormat::Yaml => utils::logging::print_yaml(&resolved),
+            OutputFormat::Text => {
+                let rows: Vec<ShowRow> = resolved
+                    .iter()
+                    .map(|r| ShowRow {
+                        key: r.key.clone(),
+                        value: r.value.clone().unwrap_or_default(),
+                        source: r.source.to_string(),
+                    })
+                    .collect();
+                let mut table = tabled::Table::new(rows);
+                table.with(Remove::row(Rows::first()));
+                utils::tabled::header_panel(&mut table, "Resolved Sideko CLI Config");
+                utils::logging::log_table(table);
+                info!("Global flags are applied through their env vars and show as `env`, the API key is masked");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(tabled::Tabled)]
+struct ShowRow {
+    key: String,
+    value: String,
+    source: String,
+}
@@ -0,0 +1,97 @@
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use regex::Regex;
+
+use crate::result::{CliError, CliResult};
+
+/// Doc source extensions whose links are checked
+const MARKDOWN_EXTENSIONS: [&str; 2] = ["md", "mdx"];
+
+/// A relative link in a doc source that does not resolve to a file
+#[derive(Debug)]
+pub struct LinkIssue {
+    pub path: String,
+    pub line: usize,
+    pub link: String,
+}
+
+/// Checks that relative links in the markdown files under the source paths point
+/// at existing files. External links, site-root links, and anchors within the same
+/// page are not checked.
+pub fn check_links(sources: &[Utf8PathBuf]) -> CliResult<Vec<LinkIssue>> {
+    let link_pattern = Regex::new(r#"\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#)
+        .expect("invalid markdown link regex pattern");
+
+    let mut issues = vec![];
+    for path in markdown_files(sources)? {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| CliError::io_custom(format!("Failed reading doc source {path}"), e))?;
+        let dir = path.parent().unwrap_or(Utf8Path::new("."));
+
+        let mut in_code_block = false;
+        for (idx, line) in content.lines().enumerate() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+
+            for capture in link_pattern.captures_iter(line) {
+                let link = &capture[1];
+                if !resolves(dir, link) {
+                    issues.push(LinkIssue {
+                        path: path.to_string(),
+                        line: idx + 1,
+                        link: link.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Whether the link is external/unchecked or points at an existing file,
+/// allowing the markdown extension to be omitted
+fn resolves(dir: &Utf8Path, link: &str) -> bool {
+    let target = link.split(['#', '?']).next().unwrap_or_default();
+    if target.is_empty() || target.starts_with('/') || target.contains(':') {
+        return true;
+    }
+
+    let target = dir.join(target);
+    target.exists()
+        || MARKDOWN_EXTENSIONS
+            .iter()
+            .any(|ext| target.with_extension(ext).is_file())
+}
+
+/// Lists the markdown files under the source paths, walking directories
+/// recursively and skipping `.git`
+fn markdown_files(sources: &[Utf8PathBuf]) -> CliResult<Vec<Utf8PathBuf>> {
+    let mut files = vec![];
+    let mut pending: Vec<Utf8PathBuf> = sources.to_vec();
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            if path.file_name() == Some(".git") {
+                continue;
+            }
+            for entry in path.read_dir_utf8().map_err(|e| {
+                CliError::io_custom(format!("Failed reading doc source directory {path}"), e)
+            })? {
+                pending.push(entry?.into_path());
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| MARKDOWN_EXTENSIONS.contains(&ext))
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -1,9 +1,10 @@
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
-use log::{debug, info};
+use camino::Utf8PathBuf;
+use log::{debug, info, warn};
 use serde_json::json;
 use sideko_rest_api::{
-    models::{Deployment, DeploymentStatusEnum, DeploymentTargetEnum},
+    models::{Deployment, DeploymentStatusEnum, DeploymentTargetEnum, DocProject},
     resources::doc::{
         self,
         deployment::{GetRequest, TriggerRequest},
@@ -13,10 +14,12 @@ use spinoff::spinners;
 
 use crate::{
     result::{CliError, CliResult},
-    styles::fmt_yellow,
+    styles::{fmt_yellow, with_emoji},
     utils::{get_sideko_client, spinner::Spinner},
 };
 
+use super::{check, manifest};
+
 #[derive(clap::Args)]
 pub struct DocDeployCommand {
     /// Doc project name or id e.g. my-docs
@@ -30,6 +33,25 @@ pub struct DocDeployCommand {
     /// Exit command after successful trigger [default: waits until deployment completes]
     #[arg(long)]
     pub no_wait: bool,
+
+    /// Validate the doc project and the relative links of the `--source` markdown
+    /// files without triggering a deployment, exits non-zero on issues
+    #[arg(long, conflicts_with_all = ["no_wait", "force"])]
+    pub dry_run: bool,
+
+    /// Doc source files or directories tracked for changes, the deploy is skipped
+    /// when none changed since the last deploy of the site (repeatable)
+    #[arg(long, value_parser = crate::utils::validators::validate_path_exists)]
+    pub source: Vec<Utf8PathBuf>,
+
+    /// Manifest recording the doc sources at each site's last completed deploy
+    /// (deploys with `--no-wait` are not recorded)
+    #[arg(long, requires = "source", default_value = manifest::DEFAULT_MANIFEST_PATH)]
+    pub manifest: Utf8PathBuf,
+
+    /// Deploy even when no doc sources changed since the last deploy
+    #[arg(long, requires = "source")]
+    pub force: bool,
 }
 impl DocDeployCommand {
     fn is_terminal_status(&self, status: &DeploymentStatusEnum) -> bool {
@@ -44,11 +66,14 @@ impl DocDeployCommand {
     }
 
     async fn poll_deployment(&self, mut deployment: Deployment) -> CliResult<Deployment> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
         let mut status = deployment.status.clone();
         let mut sp = Spinner::new(
             spinners::BouncingBall,
-            format!("📖 Deployment {}", fmt_yellow(&status.to_string())),
+            with_emoji(
+                "📖",
+                &format!("Deployment {}", fmt_yellow(&status.to_string())),
+            ),
         );
 
         while !self.is_terminal_status(&status) {
@@ -77,7 +102,7 @@ impl DocDeployCommand {
 
         match &deployment.status {
             DeploymentStatusEnum::Complete => {
-                sp.stop_success("📖 Deployment complete!");
+                sp.stop_success(with_emoji("📖", "Deployment complete!"));
             }
             DeploymentStatusEnum::Cancelled => {
                 sp.stop_warn("Deployment has been cancelled");
@@ -113,8 +138,66 @@ impl DocDeployCommand {
         Ok(deployment)
     }
 
+    /// Records the deployed doc sources in the manifest, if sources are tracked
+    fn record_deploy(
+        &self,
+        site_key: String,
+        source_hashes: Option<BTreeMap<String, String>>,
+        deployment_id: &str,
+    ) -> CliResult<()> {
+        let Some(files) = source_hashes else {
+            return Ok(());
+        };
+
+        let mut deploy_manifest = manifest::DeployManifest::load(&self.manifest)?;
+        deploy_manifest.set_site(
+            site_key,
+            manifest::SiteState {
+                deployment_id: deployment_id.to_string(),
+                deployed_at: chrono::Utc::now().to_rfc3339(),
+                files,
+            },
+        );
+        deploy_manifest.save(&self.manifest)
+    }
+
+    /// Reports issues that would affect a deployment to `target` without triggering it
+    ///
+    /// There is no server-side validation endpoint for doc deployments yet, so the
+    /// dry run is limited to the doc project itself and the local `--source` files.
+    fn dry_run(&self, doc_project: &DocProject, target: &DeploymentTargetEnum) -> CliResult<()> {
+        let domain = match target {
+            DeploymentTargetEnum::Preview => &doc_project.domains.preview,
+            DeploymentTargetEnum::Production => &doc_project.domains.production,
+        };
+        if domain.as_deref().unwrap_or_default().is_empty() {
+            warn!("Doc project `{}` has no {target} domain", self.name);
+        }
+
+        if self.source.is_empty() {
+            warn!("No --source provided, only the doc project was validated");
+        }
+        let issues = check::check_links(&self.source)?;
+        for issue in &issues {
+            warn!(
+                "{}:{}: link `{}` does not resolve to a file",
+                issue.path, issue.line, issue.link
+            );
+        }
+
+        if issues.is_empty() {
+            info!("Dry run passed, no {target} deployment was triggered");
+            Ok(())
+        } else {
+            Err(CliError::general(format!(
+                "Dry run found {} broken doc link(s)",
+                issues.len()
+            )))
+        }
+    }
+
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
 
         let target = if self.prod {
             DeploymentTargetEnum::Production
@@ -128,6 +211,43 @@ impl DocDeployCommand {
                 doc_name: self.name.clone(),
             })
             .await?;
+
+        if self.dry_run {
+            return self.dry_run(&doc_project, &target);
+        }
+
+        let site_key = format!("{}:{target}", self.name);
+        let source_hashes = if self.source.is_empty() {
+            None
+        } else {
+            let hashes = manifest::hash_sources(&self.source)?;
+            let changes = manifest::DeployManifest::load(&self.manifest)?
+                .site(&site_key)
+                .map(|site| manifest::diff(&site.files, &hashes));
+            match changes {
+                Some(changes) if changes.is_empty() && !self.force => {
+                    info!("No doc sources changed since the last {target} deploy, skipping (use --force to deploy anyway)");
+                    return Ok(());
+                }
+                Some(changes) => {
+                    for path in changes
+                        .added
+                        .iter()
+                        .chain(&changes.modified)
+                        .chain(&changes.removed)
+                    {
+                        debug!("Changed doc source: {path}");
+                    }
+                    info!(
+                        "{} doc source file(s) changed since the last {target} deploy",
+                        changes.count()
+                    );
+                }
+                None => debug!("No previous {target} deploy recorded in {}", self.manifest),
+            }
+            Some(hashes)
+        };
+
         let deployment = client
             .doc()
             .deployment()
@@ -148,6 +268,13 @@ impl DocDeployCommand {
 
         if self.no_wait {
             info!("--no-wait specified, not polling until completion");
+            // the deployment may still fail, only completed deploys are recorded
+            if source_hashes.is_some() {
+                info!(
+                    "The deploy is not recorded in {}, the next deploy will not be skipped",
+                    self.manifest
+                );
+            }
             return Ok(());
         }
 
@@ -161,6 +288,7 @@ impl DocDeployCommand {
                 );
 
                 let deployment = deployment_res?;
+                self.record_deploy(site_key, source_hashes, &deployment.id)?;
                 let url = match &deployment.target {
                     DeploymentTargetEnum::Preview => {
                         format!(
@@ -1,35 +1,109 @@
-use tabled::settings::{object::Rows, Color};
+use sideko_rest_api::{
+    models::{Deployment, DeploymentStatusEnum, DocProject},
+    resources::doc::deployment::ListRequest,
+};
+use tokio::task::JoinSet;
 
 use crate::{
     cmds::DisplayOutput,
-    result::CliResult,
-    utils::{self, get_sideko_client},
+    result::{CliError, CliResult},
+    utils::{self, get_sideko_client, retry, sideko_client_with_base_url},
 };
 
 use super::tabled::TabledDocProject;
 
 #[derive(clap::Args)]
 pub struct DocListCommand {
+    /// Only list documentation projects whose latest deployment has this status
+    #[arg(long)]
+    pub status: Option<DeploymentStatusFilter>,
+
     /// Display result as a raw json or prettified
     #[arg(long, default_value = "pretty")]
     pub display: DisplayOutput,
 }
 impl DocListCommand {
+    /// Newest deployment of each doc project, fetched concurrently within the
+    /// request limit (see `throttle::acquire`)
+    async fn latest_deployments(&self, docs: &[DocProject]) -> CliResult<Vec<Option<Deployment>>> {
+        let base_url = utils::config::get_base_url()?;
+        let mut tasks = JoinSet::new();
+        for (idx, doc) in docs.iter().enumerate() {
+            let base_url = base_url.clone();
+            let doc_name = doc.name.clone();
+            tasks.spawn(async move {
+                let res = retry::with_retries(
+                    retry::DEFAULT_ATTEMPTS,
+                    retry::Operation::Idempotent,
+                    || {
+                        let doc_name = doc_name.clone();
+                        let mut client = sideko_client_with_base_url(&base_url);
+                        async move {
+                            client
+                                .doc()
+                                .deployment()
+                                .list(ListRequest {
+                                    doc_name,
+                                    ..Default::default()
+                                })
+                                .await
+                        }
+                    },
+                )
+                .await;
+                (idx, res)
+            });
+        }
+
+        let mut latest: Vec<Option<Deployment>> = docs.iter().map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            let (idx, res) = joined.map_err(|e| {
+                CliError::general_debug("Failed fetching doc deployments", format!("{e:?}"))
+            })?;
+            // the order of the listed deployments is not guaranteed
+            latest[idx] = res?
+                .into_iter()
+                .max_by(|a, b| a.created_at.cmp(&b.created_at));
+        }
+        Ok(latest)
+    }
+
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
-        let doc_projects = client.doc().list().await?;
+        // the client is dropped before fetching the deployments, which need its request slot
+        let doc_projects = get_sideko_client().await?.doc().list().await?;
+        let latest_deployments = self.latest_deployments(&doc_projects).await?;
+
+        let mut docs_with_deployment: Vec<(_, Option<Deployment>)> = vec![];
+        for (doc, latest_deployment) in doc_projects.into_iter().zip(latest_deployments) {
+            if let Some(status) = &self.status {
+                if !latest_deployment
+                    .as_ref()
+                    .is_some_and(|d| status.matches(&d.status))
+                {
+                    continue;
+                }
+            }
+            docs_with_deployment.push((doc, latest_deployment));
+        }
 
         match &self.display {
-            DisplayOutput::Raw => utils::logging::log_json_raw(&doc_projects),
+            DisplayOutput::Raw => utils::logging::log_json_raw(
+                &docs_with_deployment
+                    .iter()
+                    .map(|(doc, _)| doc)
+                    .collect::<Vec<_>>(),
+            ),
             DisplayOutput::Pretty => {
-                let org = client.org().get().await?;
-                let mut table =
-                    tabled::Table::new(doc_projects.into_iter().map(|doc| TabledDocProject {
+                let org = get_sideko_client().await?.org().get().await?;
+                let mut table = tabled::Table::new(docs_with_deployment.into_iter().map(
+                    |(doc, latest_deployment)| TabledDocProject {
                         doc,
+                        latest_deployment,
                         org_subdomain: org.subdomain.clone(),
-                    }));
+                    },
+                ));
                 utils::tabled::header_panel(&mut table, "Documentation Projects");
-                table.modify(Rows::single(1), Color::BOLD);
+                utils::tabled::bold_row(&mut table, 1);
                 utils::logging::log_table(table);
             }
         }
@@ -37,3 +111,30 @@ impl DocListCommand {
         Ok(())
     }
 }
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum DeploymentStatusFilter {
+    /// Deployment is still in progress
+    Pending,
+    /// Deployment completed
+    Success,
+    /// Deployment errored or was cancelled
+    Failed,
+}
+impl DeploymentStatusFilter {
+    fn matches(&self, status: &DeploymentStatusEnum) -> bool {
+        match self {
+            DeploymentStatusFilter::Pending => matches!(
+                status,
+                DeploymentStatusEnum::Generated
+                    | DeploymentStatusEnum::Created
+                    | DeploymentStatusEnum::Building
+            ),
+            DeploymentStatusFilter::Success => matches!(status, DeploymentStatusEnum::Complete),
+            DeploymentStatusFilter::Failed => matches!(
+                status,
+                DeploymentStatusEnum::Error | DeploymentStatusEnum::Cancelled
+            ),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{collections::BTreeMap, fs};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use log::debug;
+use sha2::{Digest, Sha256};
+
+use crate::result::{CliError, CliResult};
+
+/// Default location of the deploy manifest, relative to the working directory
+pub const DEFAULT_MANIFEST_PATH: &str = ".sideko/doc-deploys.json";
+
+/// Content hashes of doc source files at the last deploy of each site,
+/// keyed by `<doc project>:<target>`
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct DeployManifest {
+    sites: BTreeMap<String, SiteState>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SiteState {
+    pub deployment_id: String,
+    pub deployed_at: String,
+    /// sha256 per source file path
+    pub files: BTreeMap<String, String>,
+}
+
+/// Files that differ between two snapshots of the doc sources
+#[derive(Debug, Default)]
+pub struct Changes {
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+    pub fn count(&self) -> usize {
+        self.added.len() + self.modified.len() + self.removed.len()
+    }
+}
+
+impl DeployManifest {
+    /// Loads the manifest, an empty manifest if the file does not exist yet
+    pub fn load(path: &Utf8Path) -> CliResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| {
+            CliError::io_custom(format!("Failed reading deploy manifest {path}"), e)
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            CliError::general_debug(
+                format!("Failed parsing deploy manifest {path}, remove it or use --force"),
+                format!("{e:?}"),
+            )
+        })
+    }
+
+    pub fn save(&self, path: &Utf8Path) -> CliResult<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                CliError::io_custom(format!("Failed creating directory {parent}"), e)
+            })?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            CliError::general_debug("Failed serializing deploy manifest", format!("{e:?}"))
+        })?;
+        fs::write(path, content).map_err(|e| {
+            CliError::io_custom(format!("Failed writing deploy manifest {path}"), e)
+        })?;
+        debug!("Updated deploy manifest {path}");
+        Ok(())
+    }
+
+    pub fn site(&self, key: &str) -> Option<&SiteState> {
+        self.sites.get(key)
+    }
+
+    pub fn set_site(&mut self, key: String, state: SiteState) {
+        self.sites.insert(key, state);
+    }
+}
+
+/// Changes in `current` relative to the `previous` file hashes
+pub fn diff(previous: &BTreeMap<String, String>, current: &BTreeMap<String, String>) -> Changes {
+    let mut changes = Changes::default();
+    for (path, hash) in current {
+        match previous.get(path) {
+            None => changes.added.push(path.clone()),
+            Some(prev) if prev != hash => changes.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    changes.removed = previous
+        .keys()
+        .filter(|p| !current.contains_key(*p))
+        .cloned()
+        .collect();
+    changes
+}
+
+/// Hashes every file under the source paths (directories are walked recursively,
+/// skipping `.git`), keyed by path
+pub fn hash_sources(sources: &[Utf8PathBuf]) -> CliResult<BTreeMap<String, String>> {
+    let mut hashes = BTreeMap::new();
+    let mut pending: Vec<Utf8PathBuf> = sources.to_vec();
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            if path.file_name() == Some(".git") {
+                continue;
+            }
+            for entry in path.read_dir_utf8().map_err(|e| {
+                CliError::io_custom(format!("Failed reading doc source directory {path}"), e)
+            })? {
+                pending.push(entry?.into_path());
+            }
+        } else if path.is_file() {
+            let content = fs::read(&path)
+                .map_err(|e| CliError::io_custom(format!("Failed reading doc source {path}"), e))?;
+            let hash: String = Sha256::digest(&content)
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+            hashes.insert(path.to_string(), hash);
+        }
+    }
+    Ok(hashes)
+}
@@ -1,7 +1,9 @@
 use crate::result::CliResult;
 
+mod check;
 mod deploy;
 mod list;
+mod manifest;
 mod tabled;
 
 #[derive(clap::Subcommand)]
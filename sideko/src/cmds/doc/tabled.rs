@@ -1,18 +1,24 @@
-use sideko_rest_api::models::DocProject;
+use sideko_rest_api::models::{Deployment, DocProject};
 
-use crate::utils::url_builder::DocUrl;
+use crate::{styles::with_emoji, utils::url_builder::DocUrl};
 
 pub struct TabledDocProject {
     pub doc: DocProject,
+    pub latest_deployment: Option<Deployment>,
     pub org_subdomain: String,
 }
 impl tabled::Tabled for TabledDocProject {
-    const LENGTH: usize = 5;
+    const LENGTH: usize = 6;
 
     fn fields(&self) -> Vec<std::borrow::Cow<'_, str>> {
         vec![
             self.doc.name.as_str().into(),
             self.doc.current_version.version.to_string().into(),
+            self.latest_deployment
+                .as_ref()
+                .map(|d| format!("{} ({})", d.status, d.target))
+                .unwrap_or_else(|| "-".into())
+                .into(),
             self.doc
                 .domains
                 .production
@@ -38,9 +44,10 @@ impl tabled::Tabled for TabledDocProject {
         vec![
             "Name".into(),
             "Current Version".into(),
+            "Latest Deployment".into(),
             "Production URL".into(),
             "Preview URL".into(),
-            "🔗 Link".into(),
+            with_emoji("🔗", "Link").into(),
         ]
     }
 }
@@ -16,7 +16,7 @@ pub(crate) struct LoginCommand {
     #[arg(long)]
     pub key: Option<String>,
 
-    /// Path to file to store API key, default: $HOME/.sideko
+    /// Path to file to store API key, default: $XDG_CONFIG_HOME/sideko/config on Linux, $HOME/.sideko elsewhere
     #[arg(long)]
     pub output: Option<Utf8PathBuf>,
 }
@@ -39,7 +39,7 @@ impl LoginCommand {
 
         // open browser for login
         let login_url = url::Url::parse_with_params(
-            &format!("{}/auth/login_url", utils::config::get_base_url()),
+            &format!("{}/auth/login_url", utils::config::get_base_url()?),
             &[
                 ("cli_output", output.to_string()),
                 ("cli_port", port.to_string()),
@@ -110,7 +110,13 @@ async fn login_failure(
 #[rocket::get("/login?<code>&<output>")]
 async fn login_callback(code: String, output: String) -> rocket::response::Redirect {
     // exchange code for api key
-    let mut client = utils::get_sideko_client();
+    let mut client = match utils::get_sideko_client().await {
+        Ok(client) => client,
+        Err(e) => {
+            e.log();
+            return rocket::response::Redirect::to(rocket::uri!(login_failure));
+        }
+    };
     match client
         .auth()
         .exchange_code(ExchangeCodeRequest { code })
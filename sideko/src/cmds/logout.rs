@@ -1,15 +1,52 @@
-use crate::{result::CliResult, styles::fmt_green, utils::config::ConfigKey};
+use crate::{
+    result::CliResult,
+    styles::fmt_green,
+    utils::config::{self, ConfigKey},
+};
 use log::info;
 
 #[derive(clap::Args)]
-pub(crate) struct LogoutCommand {}
+pub(crate) struct LogoutCommand {
+    /// Also remove every other stored setting (base URL, default language, ...) from
+    /// the config file, not only the API key
+    #[arg(long)]
+    pub all: bool,
+}
 
 impl LogoutCommand {
     pub async fn handle(&self) -> CliResult<()> {
-        std::env::remove_var(ConfigKey::ApiKey.to_string());
-        ConfigKey::ApiKey.unset_env()?;
-        ConfigKey::ApiKey.unset_keyring()?;
+        let keys: &[ConfigKey] = if self.all {
+            &ConfigKey::ALL
+        } else {
+            &[ConfigKey::ApiKey]
+        };
+
+        let cfg_path = config::get_config_path()?;
+        let mut cleared = 0;
+        for key in keys {
+            if key.unset_env()? {
+                info!("Removed {key} from config file {cfg_path}");
+                cleared += 1;
+            }
+        }
+        // only the API key is stored in the keyring
+        if ConfigKey::ApiKey.unset_keyring()? {
+            info!(
+                "Removed {} from keyring ({})",
+                ConfigKey::ApiKey,
+                config::keyring_backend()
+            );
+            cleared += 1;
+        }
+        // the config path and profile env vars decide which file and keyring entries
+        // are cleared above, so the process env is only cleared once they are done
+        for key in keys {
+            std::env::remove_var(key.to_string());
+        }
 
+        if cleared == 0 {
+            info!("No stored credentials found");
+        }
         info!("{} Logout successful", fmt_green("✔"));
         Ok(())
     }
@@ -4,6 +4,9 @@ pub(crate) use login::LoginCommand;
 mod logout;
 pub(crate) use logout::LogoutCommand;
 
+mod whoami;
+pub(crate) use whoami::WhoamiCommand;
+
 mod api;
 pub(crate) use api::ApiSubcommand;
 
@@ -22,3 +25,21 @@ pub enum DisplayOutput {
     Raw,
     Pretty,
 }
+
+/// Format of machine-oriented CLI output (e.g. errors)
+#[derive(clap::ValueEnum, serde::Serialize, Default, Debug, Clone, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Yaml,
+}
+impl OutputFormat {
+    /// Output format set via `--output-format` or the `SIDEKO_OUTPUT_FORMAT` env var
+    pub fn current() -> Self {
+        crate::utils::config::ConfigKey::OutputFormat
+            .get_env()
+            .and_then(|v| <Self as clap::ValueEnum>::from_str(&v, true).ok())
+            .unwrap_or_default()
+    }
+}
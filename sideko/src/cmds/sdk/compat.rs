@@ -0,0 +1,136 @@
+use std::collections::BTreeSet;
+
+use log::{info, warn};
+use regex::Regex;
+
+use crate::{
+    result::{CliError, CliResult},
+    utils::patch::{self, FilePatch},
+};
+
+/// Public symbols added and removed by an SDK update
+#[derive(Debug, Default)]
+pub struct CompatReport {
+    pub removed: BTreeSet<String>,
+    pub added: BTreeSet<String>,
+}
+impl CompatReport {
+    /// Builds the report from the update patch using language-aware heuristics,
+    /// a symbol that is removed and re-added (e.g. a signature change) is not reported
+    pub fn from_patch(patch: &str) -> Self {
+        let mut report = CompatReport::default();
+        for file in patch::parse(patch) {
+            let Some(pattern) = public_symbol_pattern(&file.path) else {
+                continue;
+            };
+            let removed = symbols(&pattern, &file, &file.removed);
+            let added = symbols(&pattern, &file, &file.added);
+
+            report.removed.extend(removed.difference(&added).cloned());
+            report.added.extend(added.difference(&removed).cloned());
+        }
+
+        report
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty()
+    }
+
+    /// Smallest semver bump covering the changes: major for removed public symbols,
+    /// minor for added ones, patch otherwise
+    pub fn bump(&self) -> Bump {
+        if self.is_breaking() {
+            Bump::Major
+        } else if !self.added.is_empty() {
+            Bump::Minor
+        } else {
+            Bump::Patch
+        }
+    }
+
+    /// Explains why `bump` was chosen
+    pub fn rationale(&self) -> String {
+        match self.bump() {
+            Bump::Major => format!("{} public symbol(s) removed", self.removed.len()),
+            Bump::Minor => format!("{} public symbol(s) added, none removed", self.added.len()),
+            Bump::Patch => "no public symbols added or removed".into(),
+        }
+    }
+}
+
+/// Semantic version bump derived from the changes of an update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+impl std::fmt::Display for Bump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let val = match self {
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        };
+        write!(f, "{val}")
+    }
+}
+
+/// Regex capturing the name of a public function/type declaration for the
+/// language of the file, `None` for files that do not define public API
+fn public_symbol_pattern(path: &str) -> Option<Regex> {
+    let extension = path.rsplit_once('.').map(|(_, ext)| ext)?;
+    let pattern = match extension {
+        "py" => r"^\s*(?:async\s+)?(?:def|class)\s+(?<name>[A-Za-z]\w*)",
+        "ts" | "js" => {
+            r"^\s*export\s+(?:default\s+)?(?:async\s+)?(?:function|class|interface|type|const|enum)\s+(?<name>\w+)"
+        }
+        "rs" => r"^\s*pub\s+(?:async\s+)?(?:fn|struct|enum|trait|type|mod|const)\s+(?<name>\w+)",
+        "go" => r"^\s*(?:func\s+(?:\([^)]*\)\s*)?|type\s+)(?<name>[A-Z]\w*)",
+        "java" => {
+            r"^\s*public\s+(?:[\w<>\[\],?]+\s+)*?(?<name>\w+)\s*(?:\(|\{|extends|implements|$)"
+        }
+        _ => return None,
+    };
+
+    Some(Regex::new(pattern).expect("invalid public symbol regex pattern"))
+}
+
+fn symbols(pattern: &Regex, file: &FilePatch, lines: &[String]) -> BTreeSet<String> {
+    lines
+        .iter()
+        .filter_map(|l| pattern.captures(l))
+        .filter_map(|c| c.name("name"))
+        .map(|name| format!("{}: {}", file.path, name.as_str()))
+        .collect()
+}
+
+/// Logs the compatibility report of the update and errors when it is
+/// breaking but `version` is not a major bump
+pub fn check(patch: &str, version: &str) -> CliResult<()> {
+    let report = CompatReport::from_patch(patch);
+    for symbol in &report.removed {
+        warn!("Breaking: removed {symbol}");
+    }
+    for symbol in &report.added {
+        info!("Added {symbol}");
+    }
+
+    if !report.is_breaking() {
+        info!("No breaking changes detected");
+        return Ok(());
+    }
+
+    match version {
+        "major" => Ok(()),
+        "patch" | "minor" | "rc" => Err(CliError::general(format!(
+            "Update removes {} public symbol(s) but --version is a {version} bump, use `--version major` for breaking changes",
+            report.removed.len()
+        ))),
+        _ => {
+            warn!("Update removes public symbols, make sure --version {version} is a major version bump");
+            Ok(())
+        }
+    }
+}
@@ -22,6 +22,10 @@ pub struct SdkConfigInitCommand {
     #[arg(long, default_value = "latest")]
     pub api_version: String,
 
+    /// Allow `latest` to resolve to a prerelease API version (e.g. `2.1.5-rc.1`) [default: stable only]
+    #[arg(long)]
+    pub allow_prerelease: bool,
+
     /// Use the `x-sideko-*` x-fields in OpenAPI to define the module structure/function names for the SDK
     ///
     /// Including this flag will cause the module config to be omitted from the generated
@@ -40,7 +44,7 @@ pub struct SdkConfigInitCommand {
 
 impl SdkConfigInitCommand {
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
 
         let customizations = if self.x_mods {
             ConfigCustomizationsEnum::XField
@@ -48,12 +52,19 @@ impl SdkConfigInitCommand {
             ConfigCustomizationsEnum::Config
         };
 
+        let api_version = utils::api_version::resolve_api_version(
+            &self.api_name,
+            &self.api_version,
+            self.allow_prerelease,
+        )
+        .await?;
+
         let config_res = client
             .sdk()
             .config()
             .init(InitRequest {
                 api_name: self.api_name.clone(),
-                api_version: Some(ApiVersion::Str(self.api_version.clone())),
+                api_version: Some(ApiVersion::Str(api_version)),
                 customizations: Some(customizations),
             })
             .await?;
@@ -65,6 +76,7 @@ impl SdkConfigInitCommand {
                 format!("{e:?}"),
             )
         })?;
+        let config = super::schema::stamp(&config);
         fs::write(&self.output, &config).map_err(|e| {
             CliError::io_custom(format!("Failed writing config to {}", &self.output), e)
         })?;
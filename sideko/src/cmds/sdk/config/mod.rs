@@ -1,6 +1,8 @@
 use crate::result::CliResult;
 
 pub(crate) mod init;
+pub(crate) mod schema;
+pub(crate) mod stats;
 pub(crate) mod sync;
 
 #[derive(clap::Subcommand)]
@@ -11,6 +13,9 @@ pub enum SdkConfigSubcommand {
 
     /// Sync SDK configuration file with an API version
     Sync(sync::SdkConfigSyncCommand),
+
+    /// Summarize a local OpenAPI specification (paths, operations, schemas, tags)
+    Stats(stats::SdkConfigStatsCommand),
 }
 
 impl SdkConfigSubcommand {
@@ -18,6 +23,7 @@ impl SdkConfigSubcommand {
         match self {
             SdkConfigSubcommand::Init(cmd) => cmd.handle().await,
             SdkConfigSubcommand::Sync(cmd) => cmd.handle().await,
+            SdkConfigSubcommand::Stats(cmd) => cmd.handle().await,
         }
     }
 }
@@ -0,0 +1,69 @@
+use std::fs;
+
+use camino::Utf8Path;
+use log::{debug, warn};
+use regex::Regex;
+
+use crate::result::{CliError, CliResult};
+
+/// Version of the SDK config format generated and supported by this CLI
+pub const SCHEMA_VERSION: u32 = 1;
+
+fn schema_version_pattern() -> Regex {
+    Regex::new(r"(?m)^#\s*schemaVersion:\s*(?<version>\d+)\s*$")
+        .expect("invalid schemaVersion regex pattern")
+}
+
+/// Stamps the schema version into the config as a leading `# schemaVersion: N` comment,
+/// replacing an existing stamp. A comment is used so the Sideko API config parser never
+/// sees an unknown field.
+pub fn stamp(config: &str) -> String {
+    let pattern = schema_version_pattern();
+    let stamp = format!("# schemaVersion: {SCHEMA_VERSION}");
+    if pattern.is_match(config) {
+        pattern.replace(config, stamp.as_str()).to_string()
+    } else {
+        format!("{stamp}\n{config}")
+    }
+}
+
+/// Reads the schema version stamped in the config, if any
+pub fn read(config: &str) -> Option<u32> {
+    schema_version_pattern()
+        .captures(config)?
+        .name("version")?
+        .as_str()
+        .parse()
+        .ok()
+}
+
+/// Checks the schema version of the config at `path` is supported by this CLI,
+/// warning on mismatch or erroring when `strict`
+pub fn check(path: &Utf8Path, strict: bool) -> CliResult<()> {
+    let config = fs::read_to_string(path)
+        .map_err(|e| CliError::io_custom(format!("Failed reading config from path: {path}"), e))?;
+
+    let msg = match read(&config) {
+        None => {
+            debug!("SDK config {path} has no schema version, skipping schema check");
+            return Ok(());
+        }
+        Some(version) if version == SCHEMA_VERSION => {
+            debug!("SDK config {path} has supported schema version {version}");
+            return Ok(());
+        }
+        Some(version) if version > SCHEMA_VERSION => format!(
+            "SDK config {path} has schema version {version}, which is newer than the version supported by this CLI ({SCHEMA_VERSION}). Update the CLI to use this config"
+        ),
+        Some(version) => format!(
+            "SDK config {path} has schema version {version}, which is older than the version supported by this CLI ({SCHEMA_VERSION}). Run `sideko sdk config sync` to migrate the config"
+        ),
+    };
+
+    if strict {
+        Err(CliError::general(msg))
+    } else {
+        warn!("{msg}");
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use log::warn;
+use tabled::settings::{object::Rows, Remove};
+
+use crate::{
+    cmds::DisplayOutput,
+    result::CliResult,
+    utils::{self, openapi::SpecStats},
+};
+
+#[derive(clap::Args)]
+pub struct SdkConfigStatsCommand {
+    /// Path to local OpenAPI specification
+    #[arg(long, value_parser = crate::utils::validators::validate_file_json_yaml)]
+    pub spec: Utf8PathBuf,
+
+    /// Display result as a raw json or prettified
+    #[arg(long, default_value = "pretty")]
+    pub display: DisplayOutput,
+}
+
+impl SdkConfigStatsCommand {
+    pub async fn handle(&self) -> CliResult<()> {
+        log_spec_stats(&self.spec, &self.display)
+    }
+}
+
+/// Parses the OpenAPI at `spec` and logs a summary of its contents, warning
+/// when the spec defines no operations
+pub fn log_spec_stats(spec: &Utf8Path, display: &DisplayOutput) -> CliResult<()> {
+    let stats = SpecStats::from_spec(&utils::openapi::load(spec)?);
+
+    match display {
+        DisplayOutput::Raw => utils::logging::log_json_raw(&stats),
+        DisplayOutput::Pretty => {
+            let summary_data = vec![
+                StatsRow::new("Paths", stats.paths),
+                StatsRow::new("Operations", stats.operations),
+                StatsRow::new("Schemas", stats.schemas),
+                StatsRow::new("Tags", stats.tags),
+            ];
+            let mut table = tabled::Table::new(summary_data);
+            table.with(Remove::row(Rows::first()));
+            utils::tabled::header_panel(&mut table, &format!("Stats: {spec}"));
+
+            utils::logging::log_table(table);
+        }
+    }
+
+    if stats.operations == 0 {
+        warn!("{spec} does not define any operations, is this the right OpenAPI?");
+    }
+
+    Ok(())
+}
+
+#[derive(tabled::Tabled)]
+struct StatsRow {
+    name: String,
+    val: String,
+}
+impl StatsRow {
+    pub fn new<N: ToString, V: ToString>(name: N, val: V) -> Self {
+        Self {
+            name: name.to_string(),
+            val: val.to_string(),
+        }
+    }
+}
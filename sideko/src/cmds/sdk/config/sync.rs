@@ -10,7 +10,10 @@ use sideko_rest_api::{
 
 use crate::{
     result::{CliError, CliResult},
-    utils::{self, get_sideko_client},
+    utils::{
+        self, get_sideko_client,
+        sdk_config::{self, SdkConfigSource},
+    },
 };
 
 #[derive(clap::Args)]
@@ -23,18 +26,24 @@ pub struct SdkConfigSyncCommand {
     #[arg(long, default_value = "latest")]
     pub version: String,
 
+    /// Allow `latest` to resolve to a prerelease API version (e.g. `2.1.5-rc.1`) [default: stable only]
+    #[arg(long)]
+    pub allow_prerelease: bool,
+
     /// Sync config with local OpenAPI specification
     #[arg(long, value_parser = crate::utils::validators::validate_file_json_yaml)]
     pub spec: Option<Utf8PathBuf>,
 
-    /// Config to sync
-    #[arg(long, value_parser = crate::utils::validators::validate_file_yaml)]
+    /// Config to sync, `-` reads the config from stdin
+    #[arg(long, value_parser = crate::utils::validators::validate_file_yaml_or_stdin)]
     pub config: Utf8PathBuf,
 
-    /// Custom output path of SDK config (must be .yaml or .yml) [defaults to same path as --config]
+    /// Custom output path of SDK config (must be .yaml or .yml) [defaults to same path as --config,
+    /// required with `--config -`]
     #[arg(
         long,
         value_parser = crate::utils::validators::validate_file_yaml_allow_dne,
+        required_if_eq("config", sdk_config::STDIN_CONFIG),
     )]
     pub output: Option<Utf8PathBuf>,
 
@@ -48,7 +57,7 @@ pub struct SdkConfigSyncCommand {
 
 impl SdkConfigSyncCommand {
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
 
         let customizations = if self.x_mods {
             ConfigCustomizationsEnum::XField
@@ -64,7 +73,13 @@ impl SdkConfigSyncCommand {
                 })?),
             )
         } else {
-            (Some(ApiVersion::Str(self.version.clone())), None)
+            let version = utils::api_version::resolve_api_version(
+                &self.name,
+                &self.version,
+                self.allow_prerelease,
+            )
+            .await?;
+            (Some(ApiVersion::Str(version)), None)
         };
 
         let synced_res = client
@@ -72,12 +87,8 @@ impl SdkConfigSyncCommand {
             .config()
             .sync(SyncRequest {
                 api_version,
-                config: UploadFile::from_path(self.config.as_str()).map_err(|e| {
-                    CliError::io_custom(
-                        format!("Failed reading config from path: {}", &self.config),
-                        e,
-                    )
-                })?,
+                config: SdkConfigSource::from_path(&self.config, sdk_config::DEFAULT_MAX_DEPTH)?
+                    .upload_file()?,
                 customizations: Some(customizations),
                 openapi,
             })
@@ -91,6 +102,7 @@ impl SdkConfigSyncCommand {
                 format!("{e:?}"),
             )
         })?;
+        let config = super::schema::stamp(&config);
         fs::write(output, &config).map_err(|e| {
             CliError::io_custom(format!("Failed writing synced config to {output}"), e)
         })?;
@@ -1,123 +1,885 @@
-use std::io::Cursor;
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, IsTerminal, Write},
+    sync::Arc,
+};
 
-use camino::Utf8PathBuf;
-use flate2::read::GzDecoder;
+use camino::{Utf8Path, Utf8PathBuf};
+use inquire::Confirm;
 
-use log::{debug, info};
-use sideko_rest_api::{models::ApiVersion, resources::sdk::GenerateRequest, UploadFile};
+use log::{debug, info, warn};
+use sha2::{Digest, Sha256};
+use sideko_rest_api::{
+    models::{ApiVersion, SdkLanguageEnum},
+    resources::sdk::GenerateRequest,
+    BinaryResponse, UploadFile,
+};
 use spinoff::spinners;
-use tar::Archive;
+use tokio::{sync::Semaphore, task::JoinSet};
 
 use crate::{
+    cmds::DisplayOutput,
     result::{CliError, CliResult},
+    styles::{fmt_green, fmt_red, with_emoji},
     utils::{
-        self,
-        {get_sideko_client, spinner::Spinner},
+        self, api_version,
+        archive::{self, SymlinkMode, Unpacker},
+        git, interrupt, prompt, retry,
+        sdk_config::{self, ConfigArg, ConfigOverride, KeyPath, SdkConfigSource},
+        validators,
+        {sideko_client_with_base_url, spinner::Spinner},
     },
 };
 
-use super::SdkLang;
+use super::{config, repo, SdkLang};
+
+/// Default number of SDKs generated concurrently
+pub const DEFAULT_MAX_CONCURRENCY: u32 = 3;
+
+/// `--output` value writing the SDK archive to stdout
+const STDOUT_OUTPUT: &str = "-";
+
+/// Config key of the package name, including the npm scope for Typescript
+pub(super) fn package_key(lang: &SdkLanguageEnum) -> Vec<String> {
+    vec!["language".into(), lang.to_string(), "package".into()]
+}
+
+/// Config key of the Go module path
+pub(super) fn go_module_key() -> Vec<String> {
+    vec![
+        "language".into(),
+        SdkLanguageEnum::Go.to_string(),
+        "module".into(),
+    ]
+}
 
-#[derive(clap::Args)]
+#[derive(clap::Args, Clone)]
+#[command(group(clap::ArgGroup::new("archive_dest").args(["archive", "keep_archive"])))]
 pub struct SdkCreateCommand {
-    /// Path to SDK config
-    #[arg(long, value_parser = crate::utils::validators::validate_file_yaml)]
-    pub config: Utf8PathBuf,
+    /// Path to SDK config, or a directory of configs (`.yaml`, `.yml`, `.json`) merged in path order,
+    /// `-` reads the config from stdin and an `http(s)://` URL fetches it
+    #[arg(long, value_parser = crate::utils::validators::validate_config_source)]
+    pub config: ConfigArg,
+
+    /// Maximum directory depth scanned when --config is a directory
+    #[arg(long, default_value_t = sdk_config::DEFAULT_MAX_DEPTH)]
+    pub max_depth: usize,
 
-    /// Programming language to generate
+    /// Fail instead of warning when the SDK config schema version is not supported by this CLI
     #[arg(long)]
-    pub lang: SdkLang,
+    pub strict: bool,
+
+    /// Programming language(s) to generate, comma-separated or repeated. With multiple
+    /// languages each SDK is saved in a `<lang>` subdirectory of --output
+    /// [default: the language set with `sideko config set-default-lang`]
+    #[arg(long, value_delimiter = ',', num_args = 1..)]
+    pub lang: Vec<SdkLang>,
+
+    /// Maximum number of SDKs generated concurrently with multiple --lang
+    #[arg(
+        long,
+        default_value_t = DEFAULT_MAX_CONCURRENCY,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub max_concurrency: u32,
 
     /// Semantic version of generated SDK
-    #[arg(long, default_value = "0.1.0")]
+    #[arg(
+        long,
+        default_value = "0.1.0",
+        value_parser = crate::utils::validators::validate_semver
+    )]
     pub version: semver::Version,
 
-    /// Generate SDK for a specific version of the API (e.g. `2.1.5`)
-    #[arg(long, default_value = "latest")]
+    /// Generate SDK for a specific version of the API (e.g. `2.1.5`), `auto` to use `info.version`
+    /// from the config, or `@<path>` to read it from a file
+    #[arg(
+        long,
+        value_parser = crate::utils::validators::validate_api_version,
+        default_value = "latest",
+    )]
     pub api_version: String,
 
-    /// Include Github actions for testing and publishing the SDK in the generation
+    /// API name or id (e.g. `my-api`) the config belongs to, required for `--api-version latest`
+    /// to resolve to its latest stable version
+    #[arg(long)]
+    pub api_name: Option<String>,
+
+    /// Allow `latest` to resolve to a prerelease API version (e.g. `2.1.5-rc.1`), without
+    /// --api-name `latest` is resolved by the Sideko API [default: stable only]
     #[arg(long)]
-    pub gh_actions: bool,
+    pub allow_prerelease: bool,
+
+    /// Github actions to include in the generated SDK, `--gh-actions` alone includes all
+    #[arg(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        default_value_t = GhActions::None,
+        default_missing_value = "all",
+    )]
+    pub gh_actions: GhActions,
 
-    /// Path to save SDK
+    /// Path to save SDK, `-` writes the SDK archive to stdout instead of unpacking it
     #[arg(
         long,
         value_parser = crate::utils::validators::validate_dir_allow_dne,
         default_value = "./",
     )]
     pub output: Utf8PathBuf,
+
+    /// Fail (exit code 3) instead of writing when the SDK destination already exists and is not empty
+    #[arg(long)]
+    pub fail_if_exists: bool,
+
+    /// Unpack into an SDK destination that already contains files without prompting,
+    /// files removed from the SDK since the previous generation are kept
+    #[arg(long, conflicts_with = "fail_if_exists")]
+    pub force: bool,
+
+    /// Remove the existing SDK destination (the SDK subdirectory of --output) before
+    /// unpacking, so it exactly matches the generated SDK
+    #[arg(long, conflicts_with = "fail_if_exists")]
+    pub clean: bool,
+
+    /// Also save the SDK archive into this directory (e.g. for `sdk unpack` in a later CI stage)
+    #[arg(long, value_parser = crate::utils::validators::validate_dir_allow_dne)]
+    pub archive: Option<Utf8PathBuf>,
+
+    /// Also save the SDK archive next to the unpacked SDK in --output, e.g. to attach to bug reports
+    #[arg(long, conflicts_with = "archive")]
+    pub keep_archive: bool,
+
+    /// Filename of the saved archive, supports `{lang}`, `{version}`, `{api_version}`, and `{date}`
+    /// placeholders (e.g. `{lang}-sdk-{version}.tar.gz`) [default: the name chosen by the server]
+    #[arg(long, requires = "archive_dest", value_name = "TEMPLATE")]
+    pub archive_name: Option<String>,
+
+    /// How symlinks in the generated SDK are unpacked [default: `copy` on Windows, `preserve` otherwise]
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = SymlinkMode::platform_default(),
+        hide_default_value = true,
+    )]
+    pub symlinks: SymlinkMode,
+
+    /// Override the package name of the generated SDK (`language.<lang>.package` in the config)
+    #[arg(long)]
+    pub package_name: Option<String>,
+
+    /// Override the Go module path of the generated SDK (`language.go.module` in the config)
+    #[arg(long)]
+    pub module_path: Option<String>,
+
+    /// Override the npm scope of the generated Typescript SDK, the scope of
+    /// `language.typescript.package` in the config
+    #[arg(long)]
+    pub npm_scope: Option<String>,
+
+    /// Omit the SDK metadata file (`.sdk.json`) from the output, e.g. for vendored code.
+    /// The SDK can then no longer be updated with `sideko sdk update`
+    #[arg(long)]
+    pub no_metadata: bool,
+
+    /// Unified diff applied to the SDK after unpacking (with `git apply`), e.g. to keep a
+    /// small local customization of a generated file. Fails if the patch does not apply
+    #[arg(long, value_name = "FILE", value_parser = crate::utils::validators::validate_file)]
+    pub post_generate_patch: Option<Utf8PathBuf>,
+
+    /// Remove values from the SDK config before upload, as a JSONPath-like key path
+    /// (e.g. `$.servers[0].url`, `languages.*.auth`), repeatable
+    #[arg(long, value_name = "PATH", value_parser = KeyPath::parse)]
+    pub redact_key: Vec<KeyPath>,
+
+    /// Print the resolved SDK config exactly as it will be uploaded (after overlays,
+    /// bundling, and redaction), or write it to the given path
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub print_config: Option<Option<Utf8PathBuf>>,
+
+    /// Log the server-side generation timing breakdown (`Server-Timing`) [default: only with --verbose]
+    #[arg(long)]
+    pub include_server_timing: bool,
+
+    /// Validate the config and options without generating the SDK or writing to --output
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Print a summary of a local OpenAPI specification before generating
+    #[arg(long, value_parser = crate::utils::validators::validate_file_json_yaml)]
+    pub spec_stats: Option<Utf8PathBuf>,
+}
+
+/// Github action workflows scaffolded into a generated SDK
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+pub enum GhActions {
+    /// No workflows
+    None,
+    /// Workflow testing the SDK
+    Test,
+    /// Workflow publishing the SDK
+    Publish,
+    /// Testing and publishing workflows
+    All,
+}
+impl GhActions {
+    /// The Sideko API currently only toggles all workflows on or off,
+    /// so any selection other than `none` includes both
+    pub fn enabled(&self) -> bool {
+        self != &GhActions::None
+    }
 }
 
 impl SdkCreateCommand {
-    pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+    /// Resolves the saved archive filename from `--archive-name`, falling back
+    /// to the server-chosen name, then `<lang>-sdk.tar.gz`
+    fn archive_name(
+        &self,
+        lang: &SdkLang,
+        server_name: Option<&str>,
+        api_version: &str,
+    ) -> CliResult<String> {
+        let Some(template) = &self.archive_name else {
+            let default_name = format!("{}-sdk.tar.gz", lang.0);
+            // the server-chosen name is joined onto --output, so it must not be a path
+            return match server_name.map(|name| (name, validators::validate_filename(name))) {
+                Some((name, Ok(()))) => Ok(name.to_string()),
+                Some((_, Err(e))) => {
+                    warn!("Ignoring the archive name chosen by the server: {e}");
+                    Ok(default_name)
+                }
+                None => Ok(default_name),
+            };
+        };
+
+        let name = template
+            .replace("{lang}", &lang.0.to_string())
+            .replace("{version}", &self.version.to_string())
+            .replace("{api_version}", api_version)
+            .replace("{date}", &chrono::Utc::now().format("%Y-%m-%d").to_string());
+        if name.contains(['{', '}']) {
+            return Err(CliError::general(format!(
+                "Unknown placeholder in --archive-name `{template}`, supported: {{lang}}, {{version}}, {{api_version}}, {{date}}"
+            )));
+        }
+        validators::validate_filename(&name).map_err(CliError::general)?;
 
-        let start = chrono::Utc::now();
+        Ok(name)
+    }
 
-        let mut sp = Spinner::new(
-            spinners::Circle,
-            format!("🪄  Generating {} SDK", self.lang.0),
-        );
-        let sdk_res = match client
-            .sdk()
-            .generate(GenerateRequest {
-                api_version: Some(ApiVersion::Str(self.api_version.clone())),
-                config: UploadFile::from_path(self.config.as_str()).map_err(|e| {
-                    CliError::io_custom(
-                        format!("Failed reading config from path: {}", &self.config),
-                        e,
-                    )
-                })?,
-                github_actions: Some(self.gh_actions),
-                language: self.lang.0.clone(),
-                sdk_version: Some(self.version.to_string()),
-            })
-            .await
+    /// Whether the SDK archive is written to stdout (`--output -`)
+    fn output_to_stdout(&self) -> bool {
+        self.output.as_str() == STDOUT_OUTPUT
+    }
+
+    /// Validates the options can be combined with `--output -`, keeping stdout
+    /// reserved for the archive
+    fn prepare_stdout_output(&self) -> CliResult<()> {
+        if self.lang.len() > 1 {
+            return Err(CliError::general(
+                "--output - only supports generating a single --lang",
+            ));
+        }
+        if self.post_generate_patch.is_some() {
+            return Err(CliError::general(
+                "--post-generate-patch cannot be used with --output -, the SDK is not unpacked",
+            ));
+        }
+        if self.archive.is_some() || self.keep_archive {
+            return Err(CliError::general(
+                "--archive and --keep-archive cannot be used with --output -, the archive is written to stdout",
+            ));
+        }
+        if matches!(self.print_config, Some(None)) {
+            return Err(CliError::general(
+                "--print-config needs a path with --output -, stdout is reserved for the archive",
+            ));
+        }
+        if io::stdout().is_terminal() && !self.dry_run {
+            warn!("--output - writes the binary SDK archive to the terminal, you probably want to redirect or pipe it (e.g. `| tar -xzf -`)");
+        }
+
+        utils::spinner::reserve_stdout();
+        Ok(())
+    }
+
+    /// Validates the language-specific overrides target one of the selected languages
+    fn validate_lang_overrides(&self) -> CliResult<()> {
+        if self.module_path.is_some()
+            && !self.lang.iter().any(|l| matches!(l.0, SdkLanguageEnum::Go))
         {
-            Ok(r) => {
-                sp.stop_success(format!(
-                    "{} {} SDK generated!",
-                    self.lang.emoji(),
-                    utils::capitalize(&self.lang.0.to_string())
-                ));
-                r
+            return Err(CliError::general(
+                "--module-path is only supported with --lang go",
+            ));
+        }
+        if self.npm_scope.is_some()
+            && !self
+                .lang
+                .iter()
+                .any(|l| matches!(l.0, SdkLanguageEnum::Typescript))
+        {
+            return Err(CliError::general(
+                "--npm-scope is only supported with --lang typescript",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Config values set by the per-language name overrides,
+    /// validated against the rules of the target language
+    fn config_overrides(
+        &self,
+        lang: &SdkLang,
+        config_source: &SdkConfigSource,
+    ) -> CliResult<Vec<ConfigOverride>> {
+        let lang = &lang.0;
+        let mut overrides = vec![];
+
+        if let (Some(module_path), SdkLanguageEnum::Go) = (&self.module_path, lang) {
+            validators::validate_go_module_path(module_path).map_err(CliError::general)?;
+            overrides.push(ConfigOverride::new(go_module_key(), module_path.clone()));
+        }
+
+        let package_key = package_key(lang);
+        let package = match (lang, &self.package_name, &self.npm_scope) {
+            (_, None, None) => None,
+            // the npm scope is part of the package name, either part not
+            // overridden is kept from the config
+            (SdkLanguageEnum::Typescript, package_name, npm_scope) => {
+                let config = config_source.load()?;
+                let configured = sdk_config::lookup_str(&config, &package_key);
+                let (configured_scope, configured_name) = match configured {
+                    Some(package) if package.starts_with('@') => package
+                        .split_once('/')
+                        .map_or((None, Some(package)), |(scope, name)| {
+                            (Some(scope), Some(name))
+                        }),
+                    package => (None, package),
+                };
+
+                let name = match package_name {
+                    Some(name) => {
+                        validators::validate_package_name(lang, name).map_err(CliError::general)?;
+                        name.as_str()
+                    }
+                    None => configured_name.ok_or_else(|| {
+                        CliError::general(format!(
+                            "--npm-scope needs --package-name, the config does not set `{}`",
+                            package_key.join(".")
+                        ))
+                    })?,
+                };
+                let scope = match npm_scope {
+                    Some(scope) => {
+                        Some(validators::validate_npm_scope(scope).map_err(CliError::general)?)
+                    }
+                    None => configured_scope.map(String::from),
+                };
+                Some(match scope {
+                    Some(scope) => format!("{scope}/{name}"),
+                    None => name.to_string(),
+                })
             }
-            Err(e) => {
-                sp.stop_error("Failed generating SDK");
-                return Err(e.into());
+            (_, Some(package_name), _) => {
+                validators::validate_package_name(lang, package_name).map_err(CliError::general)?;
+                Some(package_name.clone())
             }
+            (_, None, Some(_)) => None,
         };
+        if let Some(package) = package {
+            overrides.push(ConfigOverride::new(package_key, package));
+        }
 
-        debug!(
-            "Generation took {}s",
-            (chrono::Utc::now() - start).num_seconds()
-        );
+        if !overrides.is_empty() {
+            debug!("Applying config overrides for {lang}: {overrides:?}");
+        }
+        Ok(overrides)
+    }
 
-        debug!(
-            "Unpacking sdk to {dest}: {size} bytes",
-            dest = &self.output,
-            size = sdk_res.content.len(),
-        );
-        let decoder = GzDecoder::new(Cursor::new(&sdk_res.content));
-        let mut archive = Archive::new(decoder);
-        archive
-            .unpack(&self.output)
-            .map_err(|e| CliError::io_custom("Failed unpacking sdk archive into output", e))?;
-
-        let mut dest = self.output.clone();
-        if let Some(archive_filename) =
-            utils::response::extract_filename(&sdk_res).map(String::from)
-        {
+    /// Logs the server-side timing breakdown of the generation, separating slow
+    /// generation from slow transfer when compared to the client-side duration
+    fn log_server_timing(&self, sdk_res: &BinaryResponse) {
+        let level = if self.include_server_timing {
+            log::Level::Info
+        } else {
+            log::Level::Debug
+        };
+
+        let timings = utils::response::extract_server_timing(sdk_res);
+        if timings.is_empty() {
+            if self.include_server_timing {
+                info!("Server did not report a timing breakdown");
+            }
+            return;
+        }
+
+        for timing in timings {
+            let duration = timing
+                .duration
+                .map(|ms| format!("{ms:.0}ms"))
+                .unwrap_or_else(|| "-".into());
+            match timing.description {
+                Some(desc) => {
+                    log::log!(level, "Server timing {}: {duration} ({desc})", timing.name)
+                }
+                None => log::log!(level, "Server timing {}: {duration}", timing.name),
+            }
+        }
+    }
+
+    pub async fn handle(&self) -> CliResult<()> {
+        if matches!(self.gh_actions, GhActions::Test | GhActions::Publish) {
+            warn!("Selecting individual Github actions is not yet supported by the Sideko API, including all workflows");
+        }
+        if self.lang.is_empty() {
+            let lang = SdkLang::default_from_config()?;
+            debug!("Using default language {}", lang.0);
+            let cmd = SdkCreateCommand {
+                lang: vec![lang],
+                ..self.clone()
+            };
+            return cmd.generate().await;
+        }
+        self.generate().await
+    }
+
+    async fn generate(&self) -> CliResult<()> {
+        let config_source = SdkConfigSource::from_arg(&self.config, self.max_depth).await?;
+        for config_path in config_source.files()? {
+            config::schema::check(&config_path, self.strict)?;
+        }
+        let api_version = if self.api_version == api_version::AUTO {
+            api_version::from_config_info(&config_source.load()?, &self.config)?
+        } else {
+            api_version::resolve_config_api_version(
+                self.api_name.as_deref(),
+                &self.api_version,
+                self.allow_prerelease,
+            )
+            .await?
+        };
+        self.validate_lang_overrides()?;
+        if self.output_to_stdout() {
+            self.prepare_stdout_output()?;
+        }
+        if let Some(spec) = &self.spec_stats {
+            config::stats::log_spec_stats(spec, &DisplayOutput::Pretty)?;
+        }
+        if self.dry_run {
+            // the upload is built from the merged config, parse it to catch malformed YAML
+            config_source.load()?;
+        }
+
+        // configs are prepared up front so the generation requests can be sent concurrently
+        let mut results: Vec<Option<CliResult<()>>> = self.lang.iter().map(|_| None).collect();
+        let mut requests = vec![];
+        for (idx, lang) in self.lang.iter().enumerate() {
+            match self.prepare(lang, &config_source) {
+                Ok(config) if self.dry_run => {
+                    debug!(
+                        "Resolved {} SDK config: {} bytes",
+                        lang.0,
+                        config.content.len()
+                    );
+                    info!(
+                        "Config valid, would generate {} SDK at version {} (API version {api_version}) into {}",
+                        lang.0,
+                        self.version,
+                        self.lang_output(lang)
+                    );
+                    results[idx] = Some(Ok(()));
+                }
+                Ok(config) => requests.push((
+                    idx,
+                    GenerateRequest {
+                        api_version: Some(ApiVersion::Str(api_version.clone())),
+                        config,
+                        github_actions: Some(self.gh_actions.enabled()),
+                        language: lang.0.clone(),
+                        sdk_version: Some(self.version.to_string()),
+                    },
+                )),
+                Err(e) => results[idx] = Some(Err(e)),
+            }
+        }
+
+        if !requests.is_empty() {
+            let base_url = utils::config::get_base_url()?;
+            self.generate_all(requests, &api_version, &base_url, &mut results)
+                .await;
+        }
+        self.summarize(results)
+    }
+
+    /// Output directory of the SDK, a `<lang>` subdirectory when generating multiple languages
+    fn lang_output(&self, lang: &SdkLang) -> Utf8PathBuf {
+        if self.lang.len() > 1 {
+            self.output.join(lang.0.to_string())
+        } else {
+            self.output.clone()
+        }
+    }
+
+    /// Builds the config uploaded for `lang`, with the language overrides set
+    fn prepare(&self, lang: &SdkLang, config_source: &SdkConfigSource) -> CliResult<UploadFile> {
+        let overrides = self.config_overrides(lang, config_source)?;
+        let config = config_source.upload_file_with_overrides(&overrides, &self.redact_key)?;
+        if let Some(dest) = &self.print_config {
+            sdk_config::print_upload(&config, dest.as_deref())?;
+        }
+        Ok(config)
+    }
+
+    /// Sends the generation requests concurrently (at most `--max-concurrency` at a time)
+    /// and saves each SDK as its request completes, so unpacking is never concurrent
+    async fn generate_all(
+        &self,
+        requests: Vec<(usize, GenerateRequest)>,
+        api_version: &str,
+        base_url: &str,
+        results: &mut [Option<CliResult<()>>],
+    ) {
+        let limit = Arc::new(Semaphore::new(self.max_concurrency as usize));
+        let mut pending: Vec<usize> = requests.iter().map(|(idx, _)| *idx).collect();
+        let mut tasks = JoinSet::new();
+        // language index of each task, to find the language of a task that failed
+        let mut task_langs = HashMap::new();
+        for (idx, request) in requests {
+            let limit = limit.clone();
+            let base_url = base_url.to_string();
+            let handle = tasks.spawn(async move {
+                let _permit = limit
+                    .acquire_owned()
+                    .await
+                    .expect("concurrency semaphore is never closed");
+                let start = chrono::Utc::now();
+                let res = retry::with_retries(
+                    retry::sdk_attempts(),
+                    retry::Operation::NonIdempotent,
+                    || {
+                        let request = request.clone();
+                        let mut client = sideko_client_with_base_url(&base_url);
+                        async move { client.sdk().generate(request).await }
+                    },
+                )
+                .await;
+                (idx, res, chrono::Utc::now() - start)
+            });
+            task_langs.insert(handle.id(), idx);
+        }
+
+        let mut sp = Spinner::new(spinners::Circle, self.generating_text(&pending));
+        while let Some(joined) = tasks.join_next().await {
+            let (idx, res, took) = match joined {
+                Ok(completed) => completed,
+                Err(e) => {
+                    // the remaining requests are unaffected, the failed language is
+                    // reported as incomplete in the summary
+                    debug!("Generation task failed: {e:?}");
+                    let idx = task_langs[&e.id()];
+                    pending.retain(|i| *i != idx);
+                    sp.stop_error(format!("Failed generating {} SDK", self.lang[idx].0));
+                    if !pending.is_empty() {
+                        sp = Spinner::new(spinners::Circle, self.generating_text(&pending));
+                    }
+                    continue;
+                }
+            };
+            pending.retain(|i| *i != idx);
+            let lang = &self.lang[idx];
+
+            match res {
+                Ok(sdk_res) => {
+                    sp.stop_success(with_emoji(
+                        &lang.emoji(),
+                        &format!("{} SDK generated!", utils::capitalize(&lang.0.to_string())),
+                    ));
+                    debug!("{} generation took {}s", lang.0, took.num_seconds());
+                    results[idx] =
+                        Some(self.save(lang, &self.lang_output(lang), api_version, &sdk_res));
+                }
+                Err(e) => {
+                    sp.stop_error(format!("Failed generating {} SDK", lang.0));
+                    results[idx] = Some(Err(e.into()));
+                }
+            }
+
+            if !pending.is_empty() {
+                sp = Spinner::new(spinners::Circle, self.generating_text(&pending));
+            }
+        }
+    }
+
+    fn generating_text(&self, pending: &[usize]) -> String {
+        let langs = pending
+            .iter()
+            .map(|idx| self.lang[*idx].0.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        with_emoji("🪄 ", &format!("Generating {langs} SDK"))
+    }
+
+    /// Returns the result of a single language as is, with multiple languages every
+    /// failure is logged followed by a per-language summary
+    fn summarize(&self, results: Vec<Option<CliResult<()>>>) -> CliResult<()> {
+        let mut results: Vec<(&SdkLang, CliResult<()>)> = self
+            .lang
+            .iter()
+            .zip(results)
+            .map(|(lang, res)| {
+                let res = res.unwrap_or_else(|| {
+                    Err(CliError::general(format!(
+                        "{} SDK generation did not complete",
+                        lang.0
+                    )))
+                });
+                (lang, res)
+            })
+            .collect();
+        if results.len() == 1 {
+            return results.remove(0).1;
+        }
+
+        for (_, res) in &results {
+            if let Err(e) = res {
+                e.log();
+            }
+        }
+        let failed = results.iter().filter(|(_, res)| res.is_err()).count();
+        for (lang, res) in &results {
+            match res {
+                Ok(_) => info!(
+                    "{} {} SDK ({})",
+                    fmt_green("✔"),
+                    lang.0,
+                    self.lang_output(lang)
+                ),
+                Err(e) => info!("{} {} SDK: {}", fmt_red("✘"), lang.0, e.message()),
+            }
+        }
+        if failed > 0 {
+            return Err(CliError::general(format!(
+                "{failed} of {} SDKs failed to generate",
+                results.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Handles an SDK destination with files from a previous generation: `--clean`
+    /// removes it, `--force` (or confirming the prompt) unpacks over it
+    fn prepare_existing_dest(&self, dest: &Utf8Path) -> CliResult<()> {
+        if self.clean {
+            let removed = if dest.is_dir() {
+                fs::remove_dir_all(dest)
+            } else {
+                fs::remove_file(dest)
+            };
+            removed.map_err(|e| {
+                CliError::io_custom(format!("Failed removing previous SDK at {dest}"), e)
+            })?;
+            info!("Removed previous SDK at {dest}");
+            return Ok(());
+        }
+
+        let stale_files_note = "files removed from the SDK since the previous generation are kept, use --clean to remove them";
+        if self.force {
+            warn!("Unpacking into non-empty {dest}, {stale_files_note}");
+            return Ok(());
+        }
+        if !io::stdin().is_terminal() {
+            return Err(CliError::general(format!(
+                "SDK destination {dest} already contains files, pass --force to unpack over them or --clean to replace them"
+            )));
+        }
+
+        let message = format!("{dest} already contains files, unpack over them?");
+        let help = stale_files_note.to_string();
+        let confirmed = prompt::prompt(
+            move || {
+                Confirm::new(&message)
+                    .with_help_message(&help)
+                    .with_default(false)
+                    .prompt()
+            },
+            Some(false),
+        )?;
+        if !confirmed {
+            return Err(CliError::general(format!(
+                "Not unpacking into non-empty {dest}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Saves the generated SDK into `output`
+    fn save(
+        &self,
+        lang: &SdkLang,
+        output: &Utf8Path,
+        api_version: &str,
+        sdk_res: &BinaryResponse,
+    ) -> CliResult<()> {
+        self.log_server_timing(sdk_res);
+        verify_checksum(sdk_res)?;
+
+        if self.output_to_stdout() {
+            let mut stdout = io::stdout().lock();
+            stdout
+                .write_all(&sdk_res.content)
+                .and_then(|_| stdout.flush())
+                .map_err(|e| CliError::io_custom("Failed writing SDK archive to stdout", e))?;
+            debug!(
+                "Wrote {} SDK archive to stdout: {} bytes",
+                lang.0,
+                sdk_res.content.len()
+            );
+            return Ok(());
+        }
+
+        let server_filename = utils::response::extract_filename(sdk_res);
+        let mut dest = output.to_path_buf();
+        if let Some(archive_filename) = server_filename.as_ref().map(|f| f.to_string()) {
             dest = dest.join(
                 archive_filename
                     .strip_suffix(".tar.gz")
                     .unwrap_or(&archive_filename),
             )
         }
+        if dest == output {
+            // without the SDK subdirectory name only --output itself is known, which
+            // usually holds unrelated files (e.g. `./`), so it is neither checked nor cleaned
+            if self.clean {
+                warn!("--clean ignored: the server did not name the SDK directory, so only --output {output} is known");
+            }
+        } else if is_non_empty(&dest) {
+            if self.fail_if_exists {
+                return Err(CliError::OutputExists { path: dest });
+            }
+            self.prepare_existing_dest(&dest)?;
+        }
+
+        let _partial = (!dest.exists()).then(|| interrupt::remove_on_interrupt(&dest));
+
+        let archive_dir = match &self.archive {
+            Some(dir) => Some(dir.as_path()),
+            None => self.keep_archive.then_some(output),
+        };
+        // saved before unpacking so the archive is kept even if unpacking fails
+        if let Some(archive_dir) = archive_dir {
+            let archive_name = self.archive_name(
+                lang,
+                server_filename.as_ref().map(|f| f.as_str()),
+                api_version,
+            )?;
+            let archive_path = archive_dir.join(archive_name);
+            fs::create_dir_all(archive_dir).map_err(|e| {
+                CliError::io_custom(
+                    format!("Failed creating archive directory {archive_dir}"),
+                    e,
+                )
+            })?;
+            fs::write(&archive_path, &sdk_res.content[..]).map_err(|e| {
+                CliError::io_custom(format!("Failed saving SDK archive to {archive_path}"), e)
+            })?;
+            info!("Saved archive to {archive_path}");
+        }
+
+        debug!(
+            "Unpacking sdk to {dest}: {size} bytes",
+            dest = output,
+            size = sdk_res.content.len(),
+        );
+        let mut files = Unpacker::new(output)
+            .with_symlinks(self.symlinks.clone())
+            .unpack(&sdk_res.content[..])?;
+
+        if self.no_metadata {
+            files.retain(|f| {
+                !f.file_name()
+                    .is_some_and(|name| name == repo::METADATA_FILENAME)
+            });
+            let metadata_path = dest.join(repo::METADATA_FILENAME);
+            if metadata_path.is_file() {
+                fs::remove_file(&metadata_path).map_err(|e| {
+                    CliError::io_custom(format!("Failed removing SDK metadata {metadata_path}"), e)
+                })?;
+                debug!("Removed {metadata_path}");
+            }
+            warn!(
+                "--no-metadata: {} omitted, this SDK cannot be updated with `sideko sdk update`",
+                repo::METADATA_FILENAME
+            );
+        }
+
+        if let Some(patch) = &self.post_generate_patch {
+            apply_post_generate_patch(&dest, patch)?;
+        }
 
         info!("Saved to {dest}");
+        info!("{}", archive::file_tree(&files));
 
         Ok(())
     }
 }
+
+/// Applies the `--post-generate-patch` diff to the unpacked SDK, `git apply`
+/// leaves the SDK untouched if any hunk fails
+fn apply_post_generate_patch(dest: &Utf8Path, patch: &Utf8Path) -> CliResult<()> {
+    // git runs inside the SDK, so relative patch paths must be resolved first
+    let patch = patch.canonicalize_utf8().map_err(|e| {
+        CliError::io_custom(format!("Failed resolving post-generate patch {patch}"), e)
+    })?;
+    let dest = dest
+        .canonicalize_utf8()
+        .map_err(|e| CliError::io_custom(format!("Failed resolving SDK directory {dest}"), e))?;
+    let apply_output = git::apply_in_dir(&dest, &patch)?;
+    if !apply_output.status.success() {
+        return Err(CliError::general_debug(
+            format!("Post-generate patch {patch} does not apply to the generated SDK"),
+            git::describe_output("git apply", &apply_output),
+        ));
+    }
+
+    info!("Applied post-generate patch {patch}");
+    Ok(())
+}
+
+/// Compares the SHA-256 of the archive with the digest reported by the server (if any),
+/// so a truncated download fails clearly instead of as a gzip/tar parse error
+pub(super) fn verify_checksum(sdk_res: &BinaryResponse) -> CliResult<()> {
+    let Some(expected) = utils::response::extract_sha256_digest(sdk_res) else {
+        debug!("Server did not report an SDK archive digest, skipping checksum verification");
+        return Ok(());
+    };
+
+    let actual = Sha256::digest(&sdk_res.content);
+    if actual.as_slice() == expected.as_slice() {
+        debug!("SDK archive checksum verified");
+        return Ok(());
+    }
+
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    Err(CliError::general_debug(
+        "Downloaded SDK archive is corrupted (checksum mismatch), please retry the command",
+        format!(
+            "expected sha256 {}, got {} ({} bytes received)",
+            hex(&expected),
+            hex(&actual),
+            sdk_res.content.len()
+        ),
+    ))
+}
+
+/// Whether the path exists and is a file or a directory with any entries
+fn is_non_empty(path: &Utf8Path) -> bool {
+    if path.is_dir() {
+        path.read_dir_utf8()
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(true)
+    } else {
+        path.exists()
+    }
+}
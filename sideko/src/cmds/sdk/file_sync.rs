@@ -0,0 +1,194 @@
+use std::{collections::BTreeSet, fs};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use log::{debug, info, warn};
+
+use crate::{
+    result::{CliError, CliResult},
+    styles::{fmt_green, fmt_red, fmt_yellow},
+};
+
+/// Name of the file in the root of an SDK updated with `sdk update --no-git` listing
+/// the files the last generation produced, one relative path per line
+pub const MANIFEST_FILENAME: &str = ".sdk-files";
+
+/// File-level differences between an SDK directory and a regenerated copy of it,
+/// used by `sdk update --no-git` in place of a git patch
+#[derive(Debug, Default)]
+pub struct FileChanges {
+    pub added: Vec<Utf8PathBuf>,
+    pub modified: Vec<Utf8PathBuf>,
+    pub deleted: Vec<Utf8PathBuf>,
+}
+
+impl FileChanges {
+    /// Compares every file in `current` with `generated`, paths are relative
+    /// to the two directories. Only files of the `previous` generation are deleted,
+    /// without it every file missing from `generated` is
+    pub fn diff(
+        current: &Utf8Path,
+        generated: &Utf8Path,
+        previous: Option<&BTreeSet<Utf8PathBuf>>,
+    ) -> CliResult<Self> {
+        let current_files = relative_files(current)?;
+        let generated_files = relative_files(generated)?;
+
+        let mut changes = FileChanges::default();
+        for path in &generated_files {
+            if !current_files.contains(path) {
+                changes.added.push(path.clone());
+            } else if read(&current.join(path))? != read(&generated.join(path))? {
+                changes.modified.push(path.clone());
+            }
+        }
+        changes.deleted = current_files
+            .difference(&generated_files)
+            .filter(|path| previous.is_none_or(|previous| previous.contains(*path)))
+            .cloned()
+            .collect();
+
+        Ok(changes)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.added.len() + self.modified.len() + self.deleted.len()
+    }
+
+    /// Logs each changed path prefixed with `+` (added), `~` (modified) or `-` (deleted)
+    pub fn log(&self) {
+        for path in &self.added {
+            info!("{} {path}", fmt_green("+"));
+        }
+        for path in &self.modified {
+            info!("{} {path}", fmt_yellow("~"));
+        }
+        for path in &self.deleted {
+            info!("{} {path}", fmt_red("-"));
+        }
+    }
+
+    /// Copies added and modified files from `generated` into `current` and removes
+    /// deleted files, along with any directories they leave empty
+    pub fn apply(&self, current: &Utf8Path, generated: &Utf8Path) -> CliResult<()> {
+        for path in self.added.iter().chain(&self.modified) {
+            if through_symlink(current, path) {
+                warn!("Skipping {path}: it would be written through a symlink");
+                continue;
+            }
+            let dest = current.join(path);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    CliError::io_custom(format!("Failed creating directory {parent}"), e)
+                })?;
+            }
+            fs::copy(generated.join(path), &dest)
+                .map_err(|e| CliError::io_custom(format!("Failed writing {dest}"), e))?;
+        }
+
+        for path in &self.deleted {
+            if through_symlink(current, path) {
+                warn!("Skipping {path}: it would be deleted through a symlink");
+                continue;
+            }
+            let dest = current.join(path);
+            fs::remove_file(&dest)
+                .map_err(|e| CliError::io_custom(format!("Failed removing {dest}"), e))?;
+
+            let mut dir = dest.parent();
+            while let Some(parent) = dir.filter(|d| *d != current) {
+                // stops at the first directory that still has entries
+                if fs::remove_dir(parent).is_err() {
+                    break;
+                }
+                debug!("Removed empty directory {parent}");
+                dir = parent.parent();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Files listed in the manifest of the last `--no-git` update, `None` if the SDK
+/// has not been updated with `--no-git` before
+pub fn read_manifest(repo: &Utf8Path) -> CliResult<Option<BTreeSet<Utf8PathBuf>>> {
+    let manifest_path = repo.join(MANIFEST_FILENAME);
+    if !manifest_path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&manifest_path)
+        .map_err(|e| CliError::io_custom(format!("Failed reading {manifest_path}"), e))?;
+
+    Ok(Some(
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(Utf8PathBuf::from)
+            .collect(),
+    ))
+}
+
+/// Records every file of the generated SDK in the manifest, so the next update
+/// only deletes files a generation produced
+pub fn write_manifest(repo: &Utf8Path, generated: &Utf8Path) -> CliResult<()> {
+    let manifest_path = repo.join(MANIFEST_FILENAME);
+    let mut content = relative_files(generated)?
+        .iter()
+        .map(|p| p.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    content.push('\n');
+    fs::write(&manifest_path, content)
+        .map_err(|e| CliError::io_custom(format!("Failed writing {manifest_path}"), e))?;
+    debug!("Wrote {manifest_path}");
+
+    Ok(())
+}
+
+/// Whether `path` or one of its parent directories within `root` is a symlink,
+/// which writes and deletes must not follow out of `root`
+fn through_symlink(root: &Utf8Path, path: &Utf8Path) -> bool {
+    path.ancestors()
+        .filter(|p| !p.as_str().is_empty())
+        .any(|p| {
+            root.join(p)
+                .symlink_metadata()
+                .is_ok_and(|m| m.file_type().is_symlink())
+        })
+}
+
+fn read(path: &Utf8Path) -> CliResult<Vec<u8>> {
+    fs::read(path).map_err(|e| CliError::io_custom(format!("Failed reading {path}"), e))
+}
+
+/// Lists the files under `root` relative to it, walking directories recursively
+/// and skipping `.git` and symlinks (which are not followed)
+fn relative_files(root: &Utf8Path) -> CliResult<BTreeSet<Utf8PathBuf>> {
+    let mut files = BTreeSet::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in dir
+            .read_dir_utf8()
+            .map_err(|e| CliError::io_custom(format!("Failed reading directory {dir}"), e))?
+        {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = entry.into_path();
+            if file_type.is_symlink() {
+                debug!("Skipping symlink {path}");
+            } else if file_type.is_dir() {
+                if path.file_name() != Some(".git") {
+                    pending.push(path);
+                }
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                files.insert(relative.to_path_buf());
+            }
+        }
+    }
+    Ok(files)
+}
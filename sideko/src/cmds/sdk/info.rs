@@ -0,0 +1,134 @@
+use log::{info, warn};
+use sideko_rest_api::models::{OrganizationFeatures, SdkLanguageEnum};
+use tabled::settings::{object::Rows, Remove};
+
+use crate::{
+    cmds::OutputFormat,
+    result::CliResult,
+    utils::{self, get_sideko_client},
+};
+
+use super::{config::schema, create, SdkLang};
+
+#[derive(clap::Args)]
+pub struct SdkInfoCommand {
+    /// Programming language to show generation options for
+    #[arg(long)]
+    pub lang: SdkLang,
+}
+
+/// What can be generated for a language with the authenticated organization
+#[derive(Debug, serde::Serialize)]
+struct LangInfo {
+    language: String,
+    enabled: bool,
+    max_sdk_api_methods: i64,
+    config_schema_version: u32,
+    /// `sdk create` flags overriding language-specific config values
+    options: Vec<LangOption>,
+    /// Github action workflows included with `--gh-actions`
+    github_actions: Vec<&'static str>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct LangOption {
+    flag: &'static str,
+    config_key: String,
+}
+
+impl SdkInfoCommand {
+    /// Whether the organization's plan allows generating the language
+    fn enabled(&self, features: &OrganizationFeatures) -> bool {
+        match &self.lang.0 {
+            SdkLanguageEnum::Go => features.allow_sdk_go,
+            SdkLanguageEnum::Java => features.allow_sdk_java,
+            SdkLanguageEnum::Python => features.allow_sdk_python,
+            SdkLanguageEnum::Rust => features.allow_sdk_rust,
+            SdkLanguageEnum::Typescript => features.allow_sdk_typescript,
+        }
+    }
+
+    /// Language-specific overrides supported by `sdk create`
+    fn options(&self) -> Vec<LangOption> {
+        let lang = &self.lang.0;
+        let option = |flag, key: Vec<String>| LangOption {
+            flag,
+            config_key: key.join("."),
+        };
+
+        let mut options = vec![option("--package-name", create::package_key(lang))];
+        match lang {
+            SdkLanguageEnum::Go => options.push(option("--module-path", create::go_module_key())),
+            SdkLanguageEnum::Typescript => {
+                options.push(option("--npm-scope", create::package_key(lang)))
+            }
+            SdkLanguageEnum::Java | SdkLanguageEnum::Python | SdkLanguageEnum::Rust => {}
+        }
+        options
+    }
+
+    pub async fn handle(&self) -> CliResult<()> {
+        // there is no per-language capabilities endpoint, the organization features
+        // determine what the server generates for the authenticated org
+        let org = get_sideko_client().await?.org().get().await?;
+        let lang_info = LangInfo {
+            language: self.lang.0.to_string(),
+            enabled: self.enabled(&org.features),
+            max_sdk_api_methods: i64::from(org.features.max_sdk_api_methods),
+            config_schema_version: schema::SCHEMA_VERSION,
+            options: self.options(),
+            github_actions: vec!["test", "publish"],
+        };
+
+        match OutputFormat::current() {
+            OutputFormat::Json => utils::logging::print_json(&lang_info),
+            OutputFormat::Yaml => utils::logging::print_yaml(&lang_info),
+            OutputFormat::Text => {
+                let options = lang_info
+                    .options
+                    .iter()
+                    .map(|o| format!("{} ({})", o.flag, o.config_key))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let rows = vec![
+                    InfoRow::new("Enabled for organization", lang_info.enabled),
+                    InfoRow::new("Max API methods", lang_info.max_sdk_api_methods),
+                    InfoRow::new("Config schema version", lang_info.config_schema_version),
+                    InfoRow::new("Language options", options),
+                    InfoRow::new("Github actions", lang_info.github_actions.join(", ")),
+                ];
+                let mut table = tabled::Table::new(rows);
+                table.with(Remove::row(Rows::first()));
+                utils::tabled::header_panel(
+                    &mut table,
+                    &format!("{} SDK", utils::capitalize(&lang_info.language)),
+                );
+                utils::logging::log_table(table);
+                info!("Github actions are currently included or excluded together");
+            }
+        }
+
+        if !lang_info.enabled {
+            warn!(
+                "{} SDKs are not enabled for your organization, upgrade your plan to generate them",
+                lang_info.language
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(tabled::Tabled)]
+struct InfoRow {
+    name: String,
+    val: String,
+}
+impl InfoRow {
+    pub fn new<N: ToString, V: ToString>(name: N, val: V) -> Self {
+        Self {
+            name: name.to_string(),
+            val: val.to_string(),
+        }
+    }
+}
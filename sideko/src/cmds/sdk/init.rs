@@ -12,13 +12,19 @@ use sideko_rest_api::{
 };
 
 use crate::{
-    cmds::sdk::{config::init::SdkConfigInitCommand, create::SdkCreateCommand, SdkLang},
+    cmds::sdk::{
+        config::init::SdkConfigInitCommand,
+        create::{GhActions, SdkCreateCommand, DEFAULT_MAX_CONCURRENCY},
+        SdkLang,
+    },
     result::{CliError, CliResult},
-    styles::fmt_green,
+    styles::{emoji_or, fmt_green},
     utils::{
         self,
+        archive::SymlinkMode,
         editor::{get_editor, open_config_in_editor},
-        get_sideko_client,
+        get_sideko_client, prompt,
+        sdk_config::{self, ConfigArg},
         validators::PathKind,
     },
 };
@@ -28,15 +34,20 @@ pub struct SdkInitCommand;
 
 impl SdkInitCommand {
     async fn prompt_create_api(&self) -> CliResult<Api> {
-        let name = inquire::Text::new("API Name:")
-            .with_help_message(
-                "API name must only include lower-case alphanumeric characters and dashes",
-            )
-            .with_placeholder("my-api")
-            .with_validator(ApiNameValidator)
-            .prompt()?;
-
-        let mut client = get_sideko_client();
+        let name = prompt::prompt(
+            || {
+                inquire::Text::new("API Name:")
+                    .with_help_message(
+                        "API name must only include lower-case alphanumeric characters and dashes",
+                    )
+                    .with_placeholder("my-api")
+                    .with_validator(ApiNameValidator)
+                    .prompt()
+            },
+            None,
+        )?;
+
+        let mut client = get_sideko_client().await?;
         let new_api = client.api().create(api::CreateRequest { name }).await?;
         info!("{} API created", fmt_green("✔"));
         debug!("New API with id: {}", &new_api.id);
@@ -45,21 +56,35 @@ impl SdkInitCommand {
     }
 
     async fn prompt_create_version(&self, api: &Api) -> CliResult<ApiSpec> {
-        let oas_path = inquire::Text::new("OpenAPI:")
-            .with_help_message("Enter path to OpenAPI (≥3.0) specification for the new version")
-            .with_placeholder("path/to/spec.yml")
-            .with_validator(PathValidator::file().with_extensions(&[".json", ".yaml", ".yml"]))
-            .with_autocomplete(FilePathCompleter::default())
-            .prompt()?;
-        let version = inquire::Text::new("Version:")
-            .with_help_message(
-                "Enter the version of this API following the semantic versioning format",
-            )
-            .with_placeholder("0.1.0")
-            .with_validator(SemverValidator)
-            .prompt()?;
-
-        let mut client = get_sideko_client();
+        let oas_path = prompt::prompt(
+            || {
+                inquire::Text::new("OpenAPI:")
+                    .with_help_message(
+                        "Enter path to OpenAPI (≥3.0) specification for the new version",
+                    )
+                    .with_placeholder("path/to/spec.yml")
+                    .with_validator(
+                        PathValidator::file().with_extensions(&[".json", ".yaml", ".yml"]),
+                    )
+                    .with_autocomplete(FilePathCompleter::default())
+                    .prompt()
+            },
+            None,
+        )?;
+        let version = prompt::prompt(
+            || {
+                inquire::Text::new("Version:")
+                    .with_help_message(
+                        "Enter the version of this API following the semantic versioning format",
+                    )
+                    .with_placeholder("0.1.0")
+                    .with_validator(SemverValidator)
+                    .prompt()
+            },
+            None,
+        )?;
+
+        let mut client = get_sideko_client().await?;
         let new_version = client
             .api()
             .spec()
@@ -91,7 +116,8 @@ impl SdkInitCommand {
             let mut names: Vec<String> = options.iter().map(|a| a.name.clone()).collect();
             names.insert(0, create_new_option.into());
 
-            let choice = inquire::Select::new("Select API:", names).prompt()?;
+            let choice =
+                prompt::prompt(|| inquire::Select::new("Select API:", names).prompt(), None)?;
 
             if choice == create_new_option {
                 self.prompt_create_api().await
@@ -114,7 +140,10 @@ impl SdkInitCommand {
             let mut versions: Vec<String> = options.iter().map(|v| v.version.clone()).collect();
             versions.insert(0, create_new_option.to_string());
 
-            let choice = inquire::Select::new("Select Version:", versions).prompt()?;
+            let choice = prompt::prompt(
+                || inquire::Select::new("Select Version:", versions).prompt(),
+                None,
+            )?;
 
             if choice == create_new_option {
                 self.prompt_create_version(api).await
@@ -129,30 +158,42 @@ impl SdkInitCommand {
     }
 
     async fn select_config(&self, api: &Api, version: &ApiSpec) -> CliResult<(Utf8PathBuf, bool)> {
-        let generate_new = inquire::Confirm::new("Create SDK config?")
-            .with_default(true)
-            .prompt()?;
+        let generate_new = prompt::prompt(
+            || {
+                inquire::Confirm::new("Create SDK config?")
+                    .with_default(true)
+                    .prompt()
+            },
+            Some(true),
+        )?;
         if generate_new {
             let config_option = "SDK config customizations";
             let use_x_fields_option = "OpenAPI x-field extensions";
             let customization_options = vec![config_option, use_x_fields_option];
 
-            let res = inquire::Select::new(
-                "Select SDK customization method:",
-                customization_options,
-            )
-            .with_help_message("Choose how to customize the SDK module structure. Learn more at: https://docs.sideko.dev/sdk-generation/customizing-sdks")
-            .prompt()?;
+            let res = prompt::prompt(
+                || {
+                    inquire::Select::new("Select SDK customization method:", customization_options)
+                        .with_help_message("Choose how to customize the SDK module structure. Learn more at: https://docs.sideko.dev/sdk-generation/customizing-sdks")
+                        .prompt()
+                },
+                None,
+            )?;
 
             let is_sdk_config = res == config_option;
             Ok((self.create_config(api, version, is_sdk_config).await?, true))
         } else {
-            let config_path = inquire::Text::new("Config:")
-                .with_help_message("Enter path Sideko SDK config")
-                .with_placeholder("./sdk-config.yml")
-                .with_validator(PathValidator::file().with_extensions(&[".yaml", ".yml"]))
-                .with_autocomplete(FilePathCompleter::default())
-                .prompt()?;
+            let config_path = prompt::prompt(
+                || {
+                    inquire::Text::new("Config:")
+                        .with_help_message("Enter path Sideko SDK config")
+                        .with_placeholder("./sdk-config.yml")
+                        .with_validator(PathValidator::file().with_extensions(&[".yaml", ".yml"]))
+                        .with_autocomplete(FilePathCompleter::default())
+                        .prompt()
+                },
+                None,
+            )?;
 
             Ok((Utf8PathBuf::new().join(config_path), false))
         }
@@ -175,6 +216,7 @@ impl SdkInitCommand {
         let init_cmd = SdkConfigInitCommand {
             api_name: api.name.clone(),
             api_version: version.version.clone(),
+            allow_prerelease: false,
             x_mods: !is_sdk_config,
             output: output.clone(),
         };
@@ -186,16 +228,22 @@ impl SdkInitCommand {
 
     async fn select_languages(&self) -> CliResult<Vec<SdkLanguageEnum>> {
         // confirm feature flags for language generation
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
         let org = client.org().get().await?;
 
         let mut langs = vec![];
         let validator = SdkLanguageValidator::new(&org.features);
 
         while langs.is_empty() {
-            let input = inquire::MultiSelect::new("Select Languages:", validator.options())
-                .with_validator(validator.clone())
-                .prompt()?;
+            let (options, lang_validator) = (validator.options(), validator.clone());
+            let input = prompt::prompt(
+                || {
+                    inquire::MultiSelect::new("Select Languages:", options)
+                        .with_validator(lang_validator)
+                        .prompt()
+                },
+                None,
+            )?;
 
             // the validator ensures the casting of options to SdkLanguageEnum passes so we can use .expect here
             langs = input
@@ -212,7 +260,7 @@ impl SdkInitCommand {
     }
 
     pub async fn handle(&self) -> CliResult<()> {
-        let mut client = get_sideko_client();
+        let mut client = get_sideko_client().await?;
 
         let api_options = client.api().list().await?;
         debug!("Found {} APIs to choose from", &api_options.len());
@@ -240,24 +288,30 @@ impl SdkInitCommand {
             .await?;
 
         if stats.methods > max_sdk_methods || stats.methods < 0 {
+            let alert = emoji_or("⚠️ ⚠️ ⚠️ ", "");
             info!(
-                "⚠️ ⚠️ ⚠️ Your API has {} operations, which exceeds your limit of {}.",
+                "{alert}Your API has {} operations, which exceeds your limit of {}.",
                 stats.methods, max_sdk_methods
             );
-            info!("⚠️ ⚠️ ⚠️ Consider using the SDK config to hide operations: https://docs.sideko.dev/sdk-generation/customizing-sdks");
+            info!("{alert}Consider using the SDK config to hide operations: https://docs.sideko.dev/sdk-generation/customizing-sdks");
         }
 
         let (config, newly_generated) = self.select_config(&api, &api_version).await?;
         let generate_now = if newly_generated {
             // First ask if they want to review the config
             let editor = get_editor();
-            let review_config = inquire::Confirm::new(&format!(
-                "Review SDK config in {} before continuing? (recommended)",
-                editor
-            ))
-            .with_default(true)
-            .with_help_message("Opens config in default text editor")
-            .prompt()?;
+            let review_config = prompt::prompt(
+                move || {
+                    inquire::Confirm::new(&format!(
+                        "Review SDK config in {} before continuing? (recommended)",
+                        editor
+                    ))
+                    .with_default(true)
+                    .with_help_message("Opens config in default text editor")
+                    .prompt()
+                },
+                Some(true),
+            )?;
 
             if review_config {
                 open_config_in_editor(&config)?;
@@ -268,25 +322,52 @@ impl SdkInitCommand {
         };
         if generate_now {
             let langs = self.select_languages().await?;
-            let version = inquire::Text::new("SDK Version:")
-                .with_help_message(
-                    "Enter the version for the generated SDK(s) following the semantic versioning format",
-                )
-                .with_default("0.1.0")
-                .with_validator(SemverValidator)
-                .prompt()?;
+            let version = prompt::prompt(
+                || {
+                    inquire::Text::new("SDK Version:")
+                        .with_help_message(
+                            "Enter the version for the generated SDK(s) following the semantic versioning format",
+                        )
+                        .with_default("0.1.0")
+                        .with_validator(SemverValidator)
+                        .prompt()
+                },
+                Some("0.1.0".to_string()),
+            )?;
             for lang in langs {
                 debug!(
                     "Running `sideko sdk create --lang {} ...` with prompted input",
                     json!(&lang)
                 );
                 let create_sdk_cmd = SdkCreateCommand {
-                    config: config.clone(),
-                    lang: SdkLang(lang),
+                    config: ConfigArg::Path(config.clone()),
+                    max_depth: sdk_config::DEFAULT_MAX_DEPTH,
+                    strict: false,
+                    lang: vec![SdkLang(lang)],
+                    max_concurrency: DEFAULT_MAX_CONCURRENCY,
                     version: version.parse().expect("failed parsing sdk semver"),
                     api_version: api_version.version.clone(),
-                    gh_actions: true,
+                    api_name: Some(api.name.clone()),
+                    allow_prerelease: false,
+                    gh_actions: GhActions::All,
                     output: Utf8PathBuf::new().join("."),
+                    fail_if_exists: false,
+                    archive: None,
+                    keep_archive: false,
+                    post_generate_patch: None,
+                    force: false,
+                    clean: false,
+                    archive_name: None,
+                    symlinks: SymlinkMode::platform_default(),
+                    package_name: None,
+                    module_path: None,
+                    npm_scope: None,
+                    no_metadata: false,
+                    redact_key: vec![],
+                    print_config: None,
+                    include_server_timing: false,
+                    dry_run: false,
+                    spec_stats: None,
                 };
                 create_sdk_cmd.handle().await?;
             }
@@ -333,11 +414,9 @@ impl inquire::validator::StringValidator for SemverValidator {
         &self,
         input: &str,
     ) -> Result<inquire::validator::Validation, inquire::CustomUserError> {
-        let valid = match semver::Version::parse(input) {
+        let valid = match utils::validators::validate_semver(input) {
             Ok(_) => inquire::validator::Validation::Valid,
-            Err(e) => inquire::validator::Validation::Invalid(
-                format!("Invalid semantic version: {e}").into(),
-            ),
+            Err(e) => inquire::validator::Validation::Invalid(e.into()),
         };
 
         Ok(valid)
@@ -1,12 +1,24 @@
 use clap::{builder::PossibleValue, ValueEnum};
 use sideko_rest_api::models::SdkLanguageEnum;
 
-use crate::result::CliResult;
+use crate::{
+    result::{CliError, CliResult},
+    styles,
+    utils::config::ConfigKey,
+};
 
+mod compat;
 mod config;
 mod create;
+mod file_sync;
+mod info;
 mod init;
+pub(crate) mod repo;
+mod report;
+mod unpack;
 mod update;
+mod validate_repo;
+mod validate_spec;
 
 #[derive(clap::Subcommand)]
 pub enum SdkSubcommand {
@@ -23,8 +35,20 @@ pub enum SdkSubcommand {
     /// Create an SDK using an SDK Config
     Create(create::SdkCreateCommand),
 
+    /// Show the generation options for a language and whether it is enabled for your organization
+    Info(info::SdkInfoCommand),
+
     /// Update SDK to implement changes to APIs
     Update(update::SdkUpdateCommand),
+
+    /// Unpack a previously saved SDK archive, separating the generate and unpack steps (e.g. across CI stages)
+    Unpack(unpack::SdkUnpackCommand),
+
+    /// Check an SDK repo is ready to be updated (clean git root, SDK metadata, no leftover update files)
+    ValidateRepo(validate_repo::SdkValidateRepoCommand),
+
+    /// Validate an SDK config against an API server-side, without generating an SDK
+    ValidateSpec(validate_spec::SdkValidateSpecCommand),
 }
 
 impl SdkSubcommand {
@@ -33,7 +57,11 @@ impl SdkSubcommand {
             SdkSubcommand::Config(cmd) => cmd.handle().await,
             SdkSubcommand::Init(cmd) => cmd.handle().await,
             SdkSubcommand::Create(cmd) => cmd.handle().await,
+            SdkSubcommand::Info(cmd) => cmd.handle().await,
             SdkSubcommand::Update(cmd) => cmd.handle().await,
+            SdkSubcommand::Unpack(cmd) => cmd.handle().await,
+            SdkSubcommand::ValidateRepo(cmd) => cmd.handle().await,
+            SdkSubcommand::ValidateSpec(cmd) => cmd.handle().await,
         }
     }
 }
@@ -41,13 +69,30 @@ impl SdkSubcommand {
 #[derive(Debug, Clone)]
 pub struct SdkLang(SdkLanguageEnum);
 impl SdkLang {
+    /// Default language stored with `sideko config set-default-lang`
+    pub fn default_from_config() -> CliResult<Self> {
+        let key = ConfigKey::DefaultLang;
+        let stored = key.get_env().ok_or_else(|| {
+            CliError::general(
+                "No --lang provided and no default language is set, set one with `sideko config set-default-lang <LANG>`",
+            )
+        })?;
+        SdkLang::from_str(stored.trim(), true).map_err(|e| {
+            CliError::general_debug(
+                format!("Invalid default language `{stored}` in {key}, set a valid one with `sideko config set-default-lang <LANG>`"),
+                e,
+            )
+        })
+    }
+
+    /// Language emoji, empty when emoji are disabled
     pub fn emoji(&self) -> String {
         match &self.0 {
-            SdkLanguageEnum::Go => "🐹".into(),
-            SdkLanguageEnum::Java => "☕️".into(),
-            SdkLanguageEnum::Python => "🐍".into(),
-            SdkLanguageEnum::Rust => "🦀".into(),
-            SdkLanguageEnum::Typescript => "🟦".into(),
+            SdkLanguageEnum::Go => styles::emoji_or("🐹", ""),
+            SdkLanguageEnum::Java => styles::emoji_or("☕️", ""),
+            SdkLanguageEnum::Python => styles::emoji_or("🐍", ""),
+            SdkLanguageEnum::Rust => styles::emoji_or("🦀", ""),
+            SdkLanguageEnum::Typescript => styles::emoji_or("🟦", ""),
         }
     }
 }
@@ -0,0 +1,214 @@
+use std::{fs, sync::Arc};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use log::debug;
+
+use crate::{
+    result::{CliError, CliResult},
+    utils::{
+        git,
+        sdk_config::{self, BufferedConfig, SdkConfigSource},
+    },
+};
+
+/// Name of the patch file written into the SDK repo while applying an update
+pub const PATCH_FILENAME: &str = "sdk_update.patch";
+
+/// Name of the SDK metadata file in the root of a generated SDK
+pub const METADATA_FILENAME: &str = ".sdk.json";
+
+/// Validates the path is the root of a git repository
+///
+/// Returns: the `.git` directory path within given path
+pub fn validate_git_root(repo: &Utf8Path) -> CliResult<Utf8PathBuf> {
+    let git_dir = repo.join(".git");
+    if !(git_dir.is_dir() && git_dir.exists()) {
+        return Err(CliError::general(format!(
+            "Path is not the root of a git repository, {git_dir} not present"
+        )));
+    }
+
+    Ok(git_dir)
+}
+
+/// Validates the git working directory has no un-committed files
+pub fn validate_git_clean(repo: &Utf8Path) -> CliResult<()> {
+    let changed = git::changed_paths(repo)?;
+    if !changed.is_empty() {
+        return Err(CliError::general_debug(
+            "Git working directory is not clean. Please commit or stash your changes before updating, or use --autostash (or --allow-dirty to update anyway)",
+            format!("Uncommitted changes:\n{}", changed.join("\n")),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validates the .sdk.json file in the root of the repo has a non-empty id field
+pub fn validate_sdk_id(repo: &Utf8Path) -> CliResult<String> {
+    let md_path = repo.join(METADATA_FILENAME);
+    if !(md_path.is_file() && md_path.exists()) {
+        return Err(CliError::general_debug(
+            "Could not determine SDK ID of the repository. Is this a Sideko SDK?",
+            format!("SDK metadata path does not exist in repo: {md_path}"),
+        ));
+    }
+
+    let md_str = fs::read_to_string(&md_path).map_err(|e| {
+        CliError::general_debug(
+            "Could not determine SDK ID of the repository. Is this a Sideko SDK?",
+            format!("Unable to read SDK metadata path to string {md_path}: {e:?}"),
+        )
+    })?;
+    debug!("Found sdk metadata: {md_str}");
+
+    let md: SdkMetadata = serde_json::from_str(&md_str).map_err(|e| {
+        CliError::general_debug(
+            "Could not determine SDK ID of the repository. Is this a Sideko SDK?",
+            format!("Unable to deserialize SDK metadata path to string {md_path}: {e:?}"),
+        )
+    })?;
+    match md.id {
+        Some(id) if !id.trim().is_empty() => Ok(id),
+        _ => Err(CliError::general_debug(
+            format!("`{METADATA_FILENAME}` is missing a non-empty `id`; re-generate the SDK or set --sdk-id"),
+            format!("SDK metadata at {md_path}: {md_str}"),
+        )),
+    }
+}
+
+/// Finds files left behind by a previous update that failed to apply
+/// (the update patch and `.rej` rejects), skipping the `.git` directory
+pub fn leftover_patch_files(repo: &Utf8Path) -> CliResult<Vec<Utf8PathBuf>> {
+    let mut leftovers = vec![];
+    let mut dirs = vec![repo.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in dir.read_dir_utf8()? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                if entry.file_name() != ".git" {
+                    dirs.push(path.to_path_buf());
+                }
+            } else if entry.file_name() == PATCH_FILENAME || path.extension() == Some("rej") {
+                leftovers.push(path.to_path_buf());
+            }
+        }
+    }
+    leftovers.sort();
+
+    Ok(leftovers)
+}
+
+/// Reads the SDK config recorded in the repo's `.sdk.json`, used when `sdk update`
+/// is run without `--config`
+///
+/// Returns: the config source and where it was found, for messages
+pub fn config_from_metadata(repo: &Utf8Path) -> CliResult<(SdkConfigSource, String)> {
+    let md_path = repo.join(METADATA_FILENAME);
+    let missing_err = |debug: String| {
+        CliError::general_debug(
+            format!(
+                "No --config given and the SDK config is not recorded in {md_path}, pass --config"
+            ),
+            debug,
+        )
+    };
+
+    let md_str = fs::read_to_string(&md_path)
+        .map_err(|e| missing_err(format!("Unable to read SDK metadata {md_path}: {e:?}")))?;
+    let md: SdkMetadata = serde_json::from_str(&md_str).map_err(|e| {
+        missing_err(format!(
+            "Unable to deserialize SDK metadata {md_path}: {e:?}"
+        ))
+    })?;
+
+    match md.config {
+        Some(MetadataConfig::Path(path)) => {
+            // relative to the repo root, so the metadata stays valid wherever the repo is cloned
+            let config_path = repo.join(&path);
+            if !config_path.exists() {
+                return Err(CliError::general(format!(
+                    "SDK config `{path}` recorded in {md_path} does not exist"
+                )));
+            }
+            debug!("Using SDK config {config_path} from {md_path}");
+            let source = SdkConfigSource::from_path(&config_path, sdk_config::DEFAULT_MAX_DEPTH)?;
+            Ok((source, config_path.to_string()))
+        }
+        Some(MetadataConfig::Embedded(config)) => {
+            debug!("Using SDK config embedded in {md_path}");
+            let buffered = BufferedConfig::from_value(md_path.to_string(), &config)?;
+            Ok((
+                SdkConfigSource::Buffered(Arc::new(buffered)),
+                md_path.to_string(),
+            ))
+        }
+        None => Err(missing_err(format!(
+            "SDK metadata at {md_path} has no `config`: {md_str}"
+        ))),
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SdkMetadata {
+    /// optional so a missing id gets a targeted error instead of a deserialize failure
+    #[serde(default)]
+    pub id: Option<String>,
+    /// SDK config the SDK is generated from
+    #[serde(default)]
+    pub config: Option<MetadataConfig>,
+}
+
+/// SDK config recorded in `.sdk.json`
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum MetadataConfig {
+    /// Path to the config file or directory, relative to the repo root
+    Path(Utf8PathBuf),
+    /// The config itself
+    Embedded(serde_json::Value),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Repo dir with `metadata` written to its `.sdk.json`
+    fn repo_with_metadata(metadata: &str) -> (tempfile::TempDir, Utf8PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        fs::write(repo.join(METADATA_FILENAME), metadata).unwrap();
+        (dir, repo)
+    }
+
+    #[test]
+    fn sdk_id_is_read_from_metadata() {
+        let (_dir, repo) = repo_with_metadata(r#"{"id": "a1b2c3"}"#);
+        assert_eq!(validate_sdk_id(&repo).unwrap(), "a1b2c3");
+    }
+
+    #[test]
+    fn missing_sdk_id_is_rejected() {
+        let (_dir, repo) = repo_with_metadata(r#"{"language": "python"}"#);
+        let err = validate_sdk_id(&repo).unwrap_err();
+        assert!(err.message().contains("missing a non-empty `id`"));
+    }
+
+    #[test]
+    fn empty_sdk_id_is_rejected() {
+        for metadata in [r#"{"id": ""}"#, r#"{"id": "  "}"#] {
+            let (_dir, repo) = repo_with_metadata(metadata);
+            let err = validate_sdk_id(&repo).unwrap_err();
+            assert!(err.message().contains("missing a non-empty `id`"));
+        }
+    }
+
+    #[test]
+    fn missing_metadata_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        let err = validate_sdk_id(&repo).unwrap_err();
+        assert!(err.message().contains("Is this a Sideko SDK?"));
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use log::debug;
+
+use crate::{
+    result::{CliError, CliResult},
+    utils::{git, patch},
+};
+
+/// Structured outcome of an `sdk update` run, written with `--report-json`
+/// for automation deciding what to do with the result
+#[derive(Debug, Default, serde::Serialize)]
+pub struct UpdateReport {
+    /// Whether the Sideko API returned a non-empty patch
+    pub patch_returned: bool,
+    /// Whether the patch was applied to the repo
+    pub applied: bool,
+    pub files_changed: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Files in the patch that are ignored by git in the repo
+    pub skipped: Vec<String>,
+    pub conflicts: Vec<UpdateConflict>,
+    /// Error message when the update failed
+    pub error: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UpdateConflict {
+    pub path: String,
+    pub reason: String,
+}
+
+impl UpdateReport {
+    /// Records the contents of the returned patch
+    pub fn record_patch(&mut self, repo: &Utf8Path, patch_content: &[u8]) {
+        let files = patch::parse(&String::from_utf8_lossy(patch_content));
+        self.patch_returned = !files.is_empty();
+        self.files_changed = files.len();
+        self.lines_added = files.iter().map(|f| f.added.len()).sum();
+        self.lines_removed = files.iter().map(|f| f.removed.len()).sum();
+
+        if files.is_empty() {
+            return;
+        }
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        match git::ignored_paths(repo, &paths) {
+            Ok(ignored) => self.skipped = ignored.into_iter().map(String::from).collect(),
+            Err(e) => debug!("Failed checking ignored files in patch: {}", e.message()),
+        }
+    }
+
+    /// Records the files `git apply` could not apply from its error output
+    ///
+    /// Returns: the paths of the recorded files
+    pub fn record_apply_conflicts(&mut self, stderr: &[u8]) -> Vec<String> {
+        let mut paths = vec![];
+        for line in String::from_utf8_lossy(stderr).lines() {
+            let Some(err) = line.strip_prefix("error: ") else {
+                continue;
+            };
+            let conflict = if let Some(location) = err.strip_prefix("patch failed: ") {
+                UpdateConflict {
+                    path: location
+                        .rsplit_once(':')
+                        .map(|(path, _)| path)
+                        .unwrap_or(location)
+                        .to_string(),
+                    reason: err.to_string(),
+                }
+            } else if let Some((path, reason)) = err.split_once(": ") {
+                UpdateConflict {
+                    path: path.to_string(),
+                    reason: reason.to_string(),
+                }
+            } else {
+                continue;
+            };
+
+            // `patch failed` is usually followed by `patch does not apply` for the same file
+            if !paths.contains(&conflict.path) {
+                paths.push(conflict.path.clone());
+                self.conflicts.push(conflict);
+            }
+        }
+        paths
+    }
+
+    /// Records conflicts restoring autostashed changes over the update
+    pub fn record_stash_conflicts(&mut self, paths: &[String]) {
+        self.conflicts
+            .extend(paths.iter().map(|path| UpdateConflict {
+                path: path.clone(),
+                reason: "conflicts with stashed changes".into(),
+            }));
+    }
+
+    /// Records conflicts left by a 3-way merge of the update onto HEAD
+    pub fn record_merge_conflicts(&mut self, paths: &[String], reason: &str) {
+        self.conflicts
+            .extend(paths.iter().map(|path| UpdateConflict {
+                path: path.clone(),
+                reason: reason.into(),
+            }));
+    }
+
+    pub fn write(&self, path: &Utf8PathBuf) -> CliResult<()> {
+        let report = serde_json::to_string_pretty(self).map_err(|e| {
+            CliError::general_debug("Failed serializing update report", format!("{e:?}"))
+        })?;
+        fs::write(path, report).map_err(|e| {
+            CliError::io_custom(format!("Failed writing update report to {path}"), e)
+        })?;
+        debug!("Wrote update report to {path}");
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use log::info;
+
+use crate::{
+    result::{CliError, CliResult},
+    utils::archive::{SymlinkMode, Unpacker},
+};
+
+#[derive(clap::Args)]
+pub struct SdkUnpackCommand {
+    /// Path to a previously saved SDK archive (`.tar.gz`)
+    #[arg(long, value_parser = crate::utils::validators::validate_file)]
+    pub archive: Utf8PathBuf,
+
+    /// Path to unpack SDK into
+    #[arg(
+        long,
+        value_parser = crate::utils::validators::validate_dir_allow_dne,
+        default_value = "./",
+    )]
+    pub output: Utf8PathBuf,
+
+    /// Strip this many leading path components from archive entries (e.g. `1` drops the top-level SDK directory)
+    #[arg(long, default_value_t = 0)]
+    pub strip_components: usize,
+
+    /// Glob pattern of archive paths to skip, matched after --strip-components (repeatable)
+    #[arg(long, value_parser = crate::utils::validators::validate_glob)]
+    pub exclude: Vec<glob::Pattern>,
+
+    /// How symlinks in the archive are unpacked [default: `copy` on Windows, `preserve` otherwise]
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = SymlinkMode::platform_default(),
+        hide_default_value = true,
+    )]
+    pub symlinks: SymlinkMode,
+}
+
+impl SdkUnpackCommand {
+    pub async fn handle(&self) -> CliResult<()> {
+        let archive = fs::File::open(&self.archive).map_err(|e| {
+            CliError::io_custom(format!("Failed opening archive {}", &self.archive), e)
+        })?;
+
+        Unpacker::new(&self.output)
+            .with_symlinks(self.symlinks.clone())
+            .with_strip_components(self.strip_components)
+            .with_exclude(self.exclude.clone())
+            .unpack(std::io::BufReader::new(archive))?;
+
+        info!("Unpacked {} to {}", &self.archive, &self.output);
+        Ok(())
+    }
+}
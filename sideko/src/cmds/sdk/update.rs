@@ -1,12 +1,18 @@
-use std::{fs, io::Write, process, str};
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, BufWriter, IsTerminal, Write},
+    process, str,
+};
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use flate2::{write::GzEncoder, Compression};
 
-use log::debug;
+use inquire::Confirm;
+use log::{debug, info, warn};
 use sideko_rest_api::{
     models::{ApiVersion, VersionOrBump},
-    resources::sdk::UpdateRequest,
+    resources::sdk::{GenerateRequest, UpdateRequest},
     UploadFile,
 };
 use spinoff::spinners;
@@ -14,146 +20,459 @@ use tempfile::TempDir;
 
 use crate::{
     result::{CliError, CliResult},
-    utils::{get_sideko_client, spinner::Spinner},
+    styles::with_emoji,
+    utils::{
+        api_version,
+        archive::{self, Unpacker},
+        config::{get_base_url, ConfigKey},
+        git, interrupt, patch, prompt, retry,
+        sdk_config::{self, ConfigArg, KeyPath, SdkConfigSource},
+        sideko_client_with_base_url,
+        spinner::Spinner,
+    },
+};
+
+use super::{
+    compat, config, create,
+    file_sync::{self, FileChanges},
+    repo,
+    report::UpdateReport,
+    SdkLang,
 };
 
 #[derive(clap::Args)]
 pub struct SdkUpdateCommand {
-    /// Path to SDK config
-    #[arg(long, value_parser = crate::utils::validators::validate_file_yaml)]
-    pub config: Utf8PathBuf,
+    /// Path to SDK config, `-` to read it from stdin, or an `http(s)://` URL to fetch it from
+    /// [default: the `config` path (or embedded config) in the repo's `.sdk.json`]
+    #[arg(long, value_parser = crate::utils::validators::validate_config_file_source)]
+    pub config: Option<ConfigArg>,
+
+    /// Fail instead of warning when the SDK config schema version is not supported by this CLI
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Remove values from the SDK config before upload, as a JSONPath-like key path
+    /// (e.g. `$.servers[0].url`, `languages.*.auth`), repeatable
+    #[arg(long, value_name = "PATH", value_parser = KeyPath::parse)]
+    pub redact_key: Vec<KeyPath>,
+
+    /// Print the resolved SDK config exactly as it will be uploaded (after overlays,
+    /// bundling, and redaction), or write it to the given path
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub print_config: Option<Option<Utf8PathBuf>>,
 
     /// Path to root of SDK repo
     #[arg(long, value_parser = crate::utils::validators::validate_dir)]
     pub repo: Utf8PathBuf,
 
-    /// Semantic version of generated SDK (e.g. `2.1.5`) or version bump (`patch`, `minor`, `major`, `rc`)
+    /// Update an SDK that is not a git repository: the full SDK is regenerated and --repo
+    /// is overwritten file by file, deleting files of the previous generation the new SDK
+    /// no longer has (recorded in `.sdk-files`). Local edits cannot be detected and are
+    /// lost, preview the changes with --dry-run
+    #[arg(
+        long,
+        requires = "lang",
+        conflicts_with_all = [
+            "auto_bump", "base_ref", "git_exclude", "diff_only", "compat_check", "patch_output", "branch",
+            "commit", "autostash", "allow_dirty", "verify_clean_after_apply",
+        ]
+    )]
+    pub no_git: bool,
+
+    /// Programming language of the SDK regenerated with --no-git
+    #[arg(long, requires = "no_git")]
+    pub lang: Option<SdkLang>,
+
+    /// With --no-git and no `.sdk-files` record of the previous generation, delete every
+    /// file missing from the regenerated SDK without confirming
+    #[arg(long, requires = "no_git")]
+    pub yes: bool,
+
+    /// ID of the SDK being updated [default: the `id` in the repo's `.sdk.json`]
     #[arg(long)]
-    pub version: String,
+    pub sdk_id: Option<String>,
 
-    /// API version to update SDK with (e.g. `2.1.5`)
-    #[arg(long, default_value = "latest")]
+    /// Semantic version of generated SDK (e.g. `2.1.5`) or version bump (`patch`, `minor`, `major`, `rc`).
+    /// --no-git requires a semantic version, there is no previous version to bump
+    #[arg(
+        long,
+        required_unless_present = "auto_bump",
+        conflicts_with = "auto_bump",
+        value_parser = crate::utils::validators::validate_version_or_bump
+    )]
+    pub version: Option<String>,
+
+    /// Derive the version bump from the update: `major` when public functions/types are
+    /// removed, `minor` when they are added, `patch` otherwise. The update is generated as
+    /// a patch first, a larger bump makes a second update request, creating a second SDK
+    /// version on the server
+    #[arg(long)]
+    pub auto_bump: bool,
+
+    /// API version to update SDK with (e.g. `2.1.5`), `auto` to use `info.version` from the config,
+    /// or `@<path>` to read it from a file
+    #[arg(
+        long,
+        value_parser = crate::utils::validators::validate_api_version,
+        default_value = "latest",
+    )]
     pub api_version: String,
+
+    /// API name or id (e.g. `my-api`) the config belongs to, required for `--api-version latest`
+    /// to resolve to its latest stable version
+    #[arg(long)]
+    pub api_name: Option<String>,
+
+    /// Allow `latest` to resolve to a prerelease API version (e.g. `2.1.5-rc.1`), without
+    /// --api-name `latest` is resolved by the Sideko API [default: stable only]
+    #[arg(long)]
+    pub allow_prerelease: bool,
+
+    /// Gzip compression level (0-9) used when archiving `.git`, lower is faster but uploads more [default: 6]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(0..=9))]
+    pub compression: Option<u32>,
+
+    /// Previous SDK state to upload: `full-git` archives the entire `.git` directory,
+    /// `tree` archives only the files tracked at HEAD (much smaller for large histories)
+    #[arg(long, value_enum, default_value_t = Baseline::FullGit)]
+    pub baseline: Baseline,
+
+    /// Glob pattern of paths within `.git` to leave out of the `full-git` baseline archive
+    /// (e.g. `lfs/**` for Git LFS objects), repeatable
+    #[arg(
+        long,
+        value_name = "GLOB",
+        value_parser = crate::utils::validators::validate_glob,
+        conflicts_with = "base_ref"
+    )]
+    pub git_exclude: Vec<glob::Pattern>,
+
+    /// Generate the update against the files at this git ref (e.g. the tag of the last SDK
+    /// update) instead of HEAD, then apply it onto HEAD with a 3-way merge. For repos that
+    /// have moved on since the SDK baseline; conflicts are left as conflict markers
+    #[arg(long, value_name = "REF", conflicts_with = "baseline")]
+    pub base_ref: Option<String>,
+
+    /// How the update is applied: `3way` merges it with local changes (falling back to a
+    /// plain apply), `apply` requires it to apply cleanly, `am` applies and commits it
+    /// with `git am --3way`. `3way` and `apply` leave the update unstaged
+    #[arg(long, value_enum, default_value_t = ApplyStrategy::ThreeWay)]
+    pub strategy: ApplyStrategy,
+
+    /// Print the update as a unified diff without applying it
+    #[arg(long)]
+    pub diff_only: bool,
+
+    /// Command to pipe the diff into with --diff-only (e.g. `delta`) [env: SIDEKO_DIFF_TOOL]
+    #[arg(long, requires = "diff_only")]
+    pub diff_tool: Option<String>,
+
+    /// Check the update for breaking changes (removed public functions/types) and
+    /// fail if --version is not a major bump
+    #[arg(long)]
+    pub compat_check: bool,
+
+    /// Write the update patch (to `sdk_update.patch` in the repo, or --patch-output) and
+    /// print its `git apply --stat` summary without applying it. With --no-git, list the
+    /// files that would change
+    #[arg(
+        long,
+        conflicts_with_all = ["diff_only", "commit", "autostash", "verify_clean_after_apply"]
+    )]
+    pub dry_run: bool,
+
+    /// Where --dry-run writes the update patch [default: `sdk_update.patch` in the repo]
+    #[arg(
+        long,
+        requires = "dry_run",
+        value_parser = crate::utils::validators::validate_file_allow_dne
+    )]
+    pub patch_output: Option<Utf8PathBuf>,
+
+    /// After applying, verify the only changes in the working tree are the files in the update
+    #[arg(long, conflicts_with = "diff_only")]
+    pub verify_clean_after_apply: bool,
+
+    /// Leave an update that applied with conflicts in place instead of offering to roll it back
+    #[arg(long, conflicts_with_all = ["diff_only", "dry_run"])]
+    pub no_rollback: bool,
+
+    /// Apply the update on a new branch (`git checkout -b`) [default name: `sdk-update-<api version>`].
+    /// The branch is left checked out if applying fails
+    #[arg(
+        long,
+        value_name = "NAME",
+        num_args = 0..=1,
+        conflicts_with_all = ["diff_only", "dry_run"]
+    )]
+    pub branch: Option<Option<String>>,
+
+    /// Commit the applied update
+    #[arg(long, conflicts_with = "diff_only")]
+    pub commit: bool,
+
+    /// Author name of the update commit [env: SIDEKO_GIT_USER_NAME] [default: sideko-bot]
+    #[arg(long, requires = "commit")]
+    pub git_user_name: Option<String>,
+
+    /// Author email of the update commit [env: SIDEKO_GIT_USER_EMAIL] [default: bot@sideko.dev]
+    #[arg(long, requires = "commit")]
+    pub git_user_email: Option<String>,
+
+    /// Stash uncommitted changes before applying the update and restore them afterwards
+    #[arg(long, conflicts_with = "diff_only")]
+    pub autostash: bool,
+
+    /// Warn instead of failing when the repo has uncommitted changes, applying the update
+    /// alongside them. Cannot be combined with --commit, which would commit them too
+    #[arg(long, conflicts_with_all = ["autostash", "commit"])]
+    pub allow_dirty: bool,
+
+    /// Write a JSON summary of the run (patch returned, applied, changed files, conflicts) to this path
+    #[arg(long, value_parser = crate::utils::validators::validate_file_allow_dne)]
+    pub report_json: Option<Utf8PathBuf>,
+}
+
+/// Previous SDK state uploaded as the baseline of an update
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+pub enum Baseline {
+    /// Entire `.git` directory, including history
+    FullGit,
+    /// Files tracked at HEAD (`git archive HEAD`)
+    Tree,
+}
+
+/// How the update patch is applied to the repo
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+pub enum ApplyStrategy {
+    /// `git apply`, fails unless every hunk applies cleanly
+    Apply,
+    /// `git apply --3way`, leaving conflict markers where the update overlaps local changes
+    #[value(name = "3way")]
+    ThreeWay,
+    /// `git am --3way`, committing the update
+    Am,
 }
 
 impl SdkUpdateCommand {
     /// Validates:
-    ///     - the path is an existing directory
     ///     - the path is a valid git repo root
-    ///     - the git repo is clean (no un-committed files)
+    ///     - the git repo is clean (no un-committed files), unless `--autostash` or
+    ///       `--allow-dirty` is set
     ///
     /// Returns: the `.git` directory path within given path
     fn validate_git_root(&self) -> CliResult<Utf8PathBuf> {
-        // validate .git is present
-        let git_dir = self.repo.join(".git");
-        if !(git_dir.is_dir() && git_dir.exists()) {
-            return Err(CliError::general(format!(
-                "Path is not the root of a git repository, {git_dir} not present"
-            )));
-        }
-
-        // validate clean repo
-        let status_output = process::Command::new("git")
-            .current_dir(&self.repo)
-            .args(["status", "--porcelain"])
-            .output()
-            .map_err(|e| {
-                CliError::general_debug(
-                    "Failed to check git status, is `git` installed?",
-                    format!("{e:?}"),
-                )
-            })?;
-
-        if !status_output.stdout.is_empty() {
-            return Err(CliError::general_debug(
-                "Git working directory is not clean. Please commit or stash your changes before updating",
-                format!(
-                    "`git status` failure (exit status {exit})\nstdout:\n{stdout}\nstderr:\n{stderr}",
-                    exit = status_output.status,
-                    stdout = str::from_utf8(&status_output.stdout).unwrap_or_default(),
-                    stderr = str::from_utf8(&status_output.stderr).unwrap_or_default(),
-                )
-            ));
+        let git_dir = repo::validate_git_root(&self.repo)?;
+        if self.autostash {
+            debug!("Skipping clean working directory check, changes will be stashed before applying the update");
+        } else if self.allow_dirty {
+            let changed = git::changed_paths(&self.repo)?;
+            if !changed.is_empty() {
+                warn!("Git working directory is not clean, applying the update alongside uncommitted changes (--allow-dirty)");
+                debug!("Uncommitted changes:\n{}", changed.join("\n"));
+            }
+        } else {
+            repo::validate_git_clean(&self.repo)?;
         }
 
         Ok(git_dir)
     }
 
-    /// Validates the .sdk.json file in the root of the repo has an id field
-    pub fn validate_sdk_id(&self) -> CliResult<String> {
-        let md_path = self.repo.join(".sdk.json");
-        if !(md_path.is_file() && md_path.exists()) {
-            return Err(CliError::general_debug(
-                "Could not determine SDK ID of the repository. Is this a Sideko SDK?",
-                format!("SDK metadata path does not exist in repo: {md_path}"),
-            ));
+    /// Writes the diff to the configured diff tool, falling back to stdout
+    /// when no tool is configured or stdout is not interactive
+    fn show_diff(&self, patch: &[u8]) -> CliResult<()> {
+        let diff_tool = self
+            .diff_tool
+            .clone()
+            .or_else(|| ConfigKey::DiffTool.get_env())
+            .filter(|t| !t.trim().is_empty());
+
+        match diff_tool {
+            Some(tool) if io::stdout().is_terminal() => {
+                debug!("Piping diff into `{tool}`");
+                let mut args = tool.split_whitespace();
+                let program = args.next().unwrap_or_default();
+                let mut child = process::Command::new(program)
+                    .args(args)
+                    .stdin(process::Stdio::piped())
+                    .spawn()
+                    .map_err(|e| {
+                        CliError::io_custom(format!("Failed to run diff tool `{tool}`"), e)
+                    })?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(patch)?;
+                }
+                child.wait()?;
+            }
+            _ => io::stdout().write_all(patch)?,
         }
 
-        let md_str = fs::read_to_string(&md_path).map_err(|e| {
-            CliError::general_debug(
-                "Could not determine SDK ID of the repository. Is this a Sideko SDK?",
-                format!("Unable to read SDK metadata path to string {md_path}: {e:?}"),
-            )
-        })?;
-        debug!("Found sdk metadata: {md_str}");
+        Ok(())
+    }
 
-        let md: SdkMetadata = serde_json::from_str(&md_str).map_err(|e| {
-            CliError::general_debug(
-                "Could not determine SDK ID of the repository. Is this a Sideko SDK?",
-                format!("Unable to deserialize SDK metadata path to string {md_path}: {e:?}"),
-            )
+    /// Writes the patch for --dry-run and logs its `git apply --stat` summary
+    fn write_patch(&self, patch: &[u8]) -> CliResult<()> {
+        let patch_path = self
+            .patch_output
+            .clone()
+            .unwrap_or_else(|| self.repo.join(repo::PATCH_FILENAME));
+        fs::write(&patch_path, patch).map_err(|e| {
+            CliError::io_custom(format!("Failed writing sdk git patch file {patch_path}"), e)
         })?;
-        Ok(md.id)
+
+        if git::cli_available() {
+            self.log_patch_stat(&patch_path)?;
+        } else {
+            debug!("`git` is not installed, skipping the `git apply --stat` summary");
+        }
+
+        info!("Dry run: update written to {patch_path} without applying it, apply it with `git apply`");
+        Ok(())
+    }
+
+    /// Logs the `git apply --stat` summary of the patch
+    fn log_patch_stat(&self, patch_path: &Utf8Path) -> CliResult<()> {
+        // git runs in the repo, so the patch path must not be relative to the cwd
+        let stat_path = patch_path
+            .canonicalize_utf8()
+            .unwrap_or(patch_path.to_path_buf());
+        let stat_output = git::run(&self.repo, &["apply", "--stat", stat_path.as_str()])?;
+        if stat_output.status.success() {
+            info!(
+                "{}",
+                String::from_utf8_lossy(&stat_output.stdout).trim_end()
+            );
+        } else {
+            warn!("Failed summarizing the update patch with `git apply --stat`");
+            debug!("{}", git::describe_output("git apply --stat", &stat_output));
+        }
+        Ok(())
     }
 
     pub async fn handle(&self) -> CliResult<()> {
+        let mut report = UpdateReport::default();
+        let res = self.update(&mut report).await;
+
+        if let Some(report_path) = &self.report_json {
+            report.error = res.as_ref().err().map(|e| e.message());
+            report.write(report_path)?;
+        }
+        res
+    }
+
+    async fn update(&self, report: &mut UpdateReport) -> CliResult<()> {
         // validate and prep args
+        if self.base_ref.is_some() && self.strategy == ApplyStrategy::Apply {
+            return Err(CliError::general(
+                "--base-ref requires a 3-way merge, use --strategy 3way or am",
+            ));
+        }
+        if self.no_git {
+            return self.update_files(report).await;
+        }
+        self.require_git_cli()?;
         let git_root = self.validate_git_root()?;
-        let prev_sdk_id = self.validate_sdk_id()?;
-        let config = UploadFile::from_path(self.config.as_str()).map_err(|e| {
-            CliError::io_custom(
-                format!("Failed reading config from path: {}", &self.config),
-                e,
-            )
-        })?;
+        let prev_sdk_id = match &self.sdk_id {
+            Some(id) => id.clone(),
+            None => repo::validate_sdk_id(&self.repo)?,
+        };
+        let (config_source, api_version) = self.load_config().await?;
 
         // Create a temporary directory for the tarred .git contents
         let temp_dir = TempDir::new()
             .map_err(|e| CliError::io_custom("Failed creating temporary directory", e))?;
         debug!("Created temp directory {:?}", temp_dir.path());
 
-        // tar .git in prep for update request
+        // archive the previous SDK in prep for update request
         let archive_into = temp_dir.path().join("git.tar.gz");
-        debug!("Tarring .git into {archive_into:?}...");
-        let mut tar_gz = fs::File::create(&archive_into)?;
-        let encoder = GzEncoder::new(&tar_gz, Compression::default());
-        let mut tar = tar::Builder::new(encoder);
-        tar.append_dir_all(".", git_root)?;
-        tar.into_inner()?.finish()?;
-        tar_gz.flush()?;
-        let prev_sdk_git = UploadFile::from_path(&archive_into.to_string_lossy())?;
-        debug!("Tar complete: {} bytes", prev_sdk_git.content.len());
-
-        let mut client = get_sideko_client();
+        let tar_gz = BufWriter::new(fs::File::create(&archive_into)?);
+        let compression = self.compression.map(Compression::new).unwrap_or_default();
+        let mut encoder = GzEncoder::new(tar_gz, compression);
+        // an update against --base-ref is generated from the files at that ref
+        let (baseline, tree_ref) = match &self.base_ref {
+            Some(base_ref) => (&Baseline::Tree, self.resolve_base_ref(base_ref)?),
+            None => (&self.baseline, "HEAD".to_string()),
+        };
+        match baseline {
+            Baseline::FullGit => {
+                debug!("Tarring .git into {archive_into:?}...");
+                let mut tar = tar::Builder::new(encoder);
+                archive::append_dir_filtered(&mut tar, &git_root, &self.git_exclude)?;
+                tar.into_inner()?.finish()?.flush()?;
+            }
+            Baseline::Tree => {
+                debug!("Archiving tracked files at {tree_ref} into {archive_into:?}...");
+                let archive_output = git::run(&self.repo, &["archive", "--format=tar", &tree_ref])?;
+                if !archive_output.status.success() {
+                    return Err(CliError::general_debug(
+                        format!("Failed to archive the SDK repository at {tree_ref}"),
+                        git::describe_output(
+                            &format!("git archive --format=tar {tree_ref}"),
+                            &archive_output,
+                        ),
+                    ));
+                }
+                encoder.write_all(&archive_output.stdout)?;
+                encoder.finish()?.flush()?;
+            }
+        }
+        debug!("Tar complete: {} bytes", fs::metadata(&archive_into)?.len());
+
+        // with --auto-bump the update is generated as a patch bump first, then
+        // re-generated if its changes call for a larger bump
+        let mut version = match &self.version {
+            Some(version) => version.clone(),
+            None => compat::Bump::Patch.to_string(),
+        };
+        let mut bumped = false;
 
+        let base_url = get_base_url()?;
         let start = chrono::Utc::now();
-        let mut sp = Spinner::new(spinners::Circle, "🪄  Updating SDK");
-        let patch_content = match client
-            .sdk()
-            .update(UpdateRequest {
-                api_version: Some(ApiVersion::Str(self.api_version.clone())),
-                config,
-                prev_sdk_git,
-                prev_sdk_id,
-                sdk_version: VersionOrBump::Str(self.version.clone()),
-            })
-            .await
-        {
-            Ok(p) => p,
-            Err(e) => {
-                sp.stop_error("Failed updating SDK");
-                return Err(e.into());
+        let mut sp = Spinner::new(spinners::Circle, with_emoji("🪄 ", "Updating SDK"));
+        let patch_content = loop {
+            let request = UpdateRequest {
+                api_version: Some(ApiVersion::Str(api_version.clone())),
+                config: config_source.upload_file_with_overrides(&[], &self.redact_key)?,
+                prev_sdk_git: UploadFile::from_path(&archive_into.to_string_lossy())?,
+                prev_sdk_id: prev_sdk_id.clone(),
+                sdk_version: VersionOrBump::Str(version.clone()),
+            };
+            let res = retry::with_retries(
+                retry::sdk_attempts(),
+                retry::Operation::NonIdempotent,
+                || {
+                    let request = request.clone();
+                    let mut client = sideko_client_with_base_url(&base_url);
+                    async move { client.sdk().update(request).await }
+                },
+            )
+            .await;
+            let patch_content = match res {
+                Ok(p) => p,
+                Err(e) => {
+                    sp.stop_error("Failed updating SDK");
+                    return Err(e.into());
+                }
+            };
+
+            if !self.auto_bump || bumped || patch_content.is_empty() {
+                break patch_content;
             }
+            bumped = true;
+
+            let compat_report =
+                compat::CompatReport::from_patch(&String::from_utf8_lossy(patch_content.as_ref()));
+            let bump = compat_report.bump();
+            info!("Auto bump: {bump} ({})", compat_report.rationale());
+            if bump == compat::Bump::Patch {
+                break patch_content;
+            }
+            version = bump.to_string();
+            warn!("Auto bump: requesting a second update with a {bump} bump, the patch version generated first also remains on the server");
+            sp.update_text(with_emoji(
+                "🪄 ",
+                &format!("Updating SDK with a {bump} bump"),
+            ));
         };
 
         debug!(
@@ -165,45 +484,601 @@ impl SdkUpdateCommand {
             sp.stop_warn("No updates to apply");
             return Ok(());
         }
+        if self.report_json.is_some() {
+            report.record_patch(&self.repo, patch_content.as_ref());
+        }
 
-        // write and apply git patch
-        let patch_filename = "sdk_update.patch";
-        let patch_path = self.repo.join(patch_filename);
-        fs::write(&patch_path, &patch_content)
-            .map_err(|e| CliError::io_custom("Failed writing sdk git patch file", e))?;
-
-        let patch_output = process::Command::new("git")
-            .current_dir(&self.repo)
-            .arg("apply")
-            .arg(patch_filename)
-            .output()
-            .map_err(|e| {
-                CliError::general_debug(
-                    "Failed to run git patch, is `git` installed?",
-                    format!("{e:?}"),
-                )
+        if self.compat_check || self.diff_only || self.dry_run {
+            sp.stop_success("Update generated");
+        }
+
+        if self.compat_check {
+            compat::check(&String::from_utf8_lossy(patch_content.as_ref()), &version)?;
+        }
+
+        if self.diff_only {
+            return self.show_diff(patch_content.as_ref());
+        }
+        if self.dry_run {
+            return self.write_patch(patch_content.as_ref());
+        }
+
+        if let Some(branch) = &self.branch {
+            let branch = branch
+                .clone()
+                .unwrap_or_else(|| format!("sdk-update-{api_version}"));
+            self.create_branch(&branch)?;
+        }
+        if self.compat_check {
+            sp = Spinner::new(spinners::Circle, "Applying update");
+        }
+
+        let stashed = self.autostash && self.stash_push()?;
+        let applied = self.apply_patch(patch_content.as_ref(), &version, &mut sp, report);
+        if stashed {
+            self.stash_pop(report)?;
+        }
+        applied
+    }
+
+    /// Loads and checks the SDK config, printing it with --print-config
+    ///
+    /// Returns: the config and the API version to update with
+    async fn load_config(&self) -> CliResult<(SdkConfigSource, String)> {
+        let (config_source, config_origin) = match &self.config {
+            Some(config) => (
+                SdkConfigSource::from_arg(config, sdk_config::DEFAULT_MAX_DEPTH).await?,
+                config.to_string(),
+            ),
+            None => repo::config_from_metadata(&self.repo)?,
+        };
+        for config_path in config_source.files()? {
+            config::schema::check(&config_path, self.strict)?;
+        }
+        let api_version = if self.api_version == api_version::AUTO {
+            api_version::from_config_info(&config_source.load()?, &config_origin)?
+        } else {
+            api_version::resolve_config_api_version(
+                self.api_name.as_deref(),
+                &self.api_version,
+                self.allow_prerelease,
+            )
+            .await?
+        };
+        if let Some(dest) = &self.print_config {
+            let config = config_source.upload_file_with_overrides(&[], &self.redact_key)?;
+            sdk_config::print_upload(&config, dest.as_deref())?;
+        }
+        Ok((config_source, api_version))
+    }
+
+    /// `--no-git` update: regenerates the full SDK and syncs --repo to it file by file
+    async fn update_files(&self, report: &mut UpdateReport) -> CliResult<()> {
+        let lang = self
+            .lang
+            .as_ref()
+            .expect("--lang is required with --no-git");
+        // without git history there is no previous version for the server to bump
+        if let Some(version) = &self.version {
+            crate::utils::validators::validate_semver(version).map_err(|e| {
+                CliError::general(format!(
+                    "--no-git requires --version to be a semantic version: {e}"
+                ))
             })?;
+        }
+        let (config_source, api_version) = self.load_config().await?;
+        warn!(
+            "--no-git: local edits in {} cannot be detected, every file that differs from the regenerated SDK is overwritten",
+            self.repo
+        );
+        let previous = file_sync::read_manifest(&self.repo)?;
 
-        if patch_output.status.success() {
-            sp.stop_success("🚀 Update applied!");
+        let request = GenerateRequest {
+            api_version: Some(ApiVersion::Str(api_version)),
+            config: config_source.upload_file_with_overrides(&[], &self.redact_key)?,
+            github_actions: None,
+            language: lang.0.clone(),
+            sdk_version: self.version.clone(),
+        };
+        let base_url = get_base_url()?;
+        let mut sp = Spinner::new(spinners::Circle, with_emoji("🪄 ", "Regenerating SDK"));
+        let res = retry::with_retries(
+            retry::sdk_attempts(),
+            retry::Operation::NonIdempotent,
+            || {
+                let request = request.clone();
+                let mut client = sideko_client_with_base_url(&base_url);
+                async move { client.sdk().generate(request).await }
+            },
+        )
+        .await;
+        let sdk_res = match res {
+            Ok(sdk_res) => sdk_res,
+            Err(e) => {
+                sp.stop_error("Failed regenerating SDK");
+                return Err(e.into());
+            }
+        };
+        create::verify_checksum(&sdk_res)?;
+
+        let temp_dir = TempDir::new()
+            .map_err(|e| CliError::io_custom("Failed creating temporary directory", e))?;
+        let generated = Utf8PathBuf::try_from(temp_dir.path().join("sdk")).map_err(|e| {
+            CliError::general_debug("Temporary directory path is not UTF-8", format!("{e:?}"))
+        })?;
+        // the archive holds the SDK in a single top-level directory
+        Unpacker::new(&generated)
+            .with_strip_components(1)
+            .unpack(&sdk_res.content[..])?;
+
+        let mut changes = FileChanges::diff(&self.repo, &generated, previous.as_ref())?;
+        report.patch_returned = !changes.is_empty();
+        report.files_changed = changes.len();
+        if changes.is_empty() {
+            sp.stop_warn("No updates to apply");
+            return Ok(());
+        }
+
+        if self.dry_run {
+            sp.stop_success("Update generated");
+            changes.log();
+            if previous.is_none() && !changes.deleted.is_empty() {
+                warn!(
+                    "{} has no {}, deletions are confirmed before they are applied (or use --yes)",
+                    self.repo,
+                    file_sync::MANIFEST_FILENAME
+                );
+            }
+            info!(
+                "Dry run: {} file(s) in {} would change, nothing was written",
+                changes.len(),
+                self.repo
+            );
+            return Ok(());
+        }
+
+        sp.stop_success("Update generated");
+        if previous.is_none() && !changes.deleted.is_empty() && !self.confirm_deletes(&changes)? {
+            changes.deleted.clear();
+        }
+        changes.apply(&self.repo, &generated)?;
+        file_sync::write_manifest(&self.repo, &generated)?;
+        report.applied = true;
+        report.files_changed = changes.len();
+        info!("{}", with_emoji("🚀", "Update applied!"));
+        changes.log();
+        Ok(())
+    }
+
+    /// Without a record of the previous generation, files missing from the regenerated
+    /// SDK may be the user's own (`.env`, build output), so deleting them is confirmed
+    ///
+    /// Returns: whether to delete them
+    fn confirm_deletes(&self, changes: &FileChanges) -> CliResult<bool> {
+        if self.yes {
+            return Ok(true);
+        }
+        for path in &changes.deleted {
+            warn!("Not in the regenerated SDK: {path}");
+        }
+        if !io::stdin().is_terminal() {
+            warn!(
+                "Keeping {} file(s) missing from the regenerated SDK, there is no {} recording the previous generation. Use --yes to delete them",
+                changes.deleted.len(),
+                file_sync::MANIFEST_FILENAME
+            );
+            return Ok(false);
+        }
+
+        let count = changes.deleted.len();
+        prompt::prompt(
+            move || {
+                Confirm::new(&format!(
+                    "Delete the {count} file(s) above that the regenerated SDK does not have?"
+                ))
+                .with_help_message(
+                    "they may not have been generated, 'n' keeps them and updates the rest",
+                )
+                .with_default(false)
+                .prompt()
+            },
+            Some(false),
+        )
+    }
+
+    /// Checks the `git` CLI is installed for the options libgit2 does not cover, before
+    /// anything is generated
+    fn require_git_cli(&self) -> CliResult<()> {
+        let needs_cli = [
+            (self.branch.is_some(), "--branch"),
+            (self.autostash, "--autostash"),
+            (self.commit, "--commit"),
+            (self.base_ref.is_some(), "--base-ref"),
+            (self.baseline == Baseline::Tree, "--baseline tree"),
+            (self.strategy == ApplyStrategy::Am, "--strategy am"),
+        ];
+        for (needed, option) in needs_cli {
+            if needed {
+                git::require_cli(option)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Paths left with conflict markers by a 3-way apply, `None` with
+    /// `--strategy apply` or when the apply failed without conflicts
+    fn merge_conflicts(&self) -> CliResult<Option<Vec<String>>> {
+        if self.strategy == ApplyStrategy::Apply || !git::cli_available() {
+            return Ok(None);
+        }
+        let conflicts = git::conflicted_paths(&self.repo)?;
+        Ok((!conflicts.is_empty()).then_some(conflicts))
+    }
+
+    /// Creates and checks out the `--branch` the update is applied on
+    fn create_branch(&self, branch: &str) -> CliResult<()> {
+        let checkout_output = git::run(&self.repo, &["checkout", "-b", branch])?;
+        if !checkout_output.status.success() {
+            return Err(CliError::general_debug(
+                format!("Failed to create branch `{branch}`, does it already exist?"),
+                git::describe_output(&format!("git checkout -b {branch}"), &checkout_output),
+            ));
+        }
+        info!("Switched to new branch `{branch}`");
+        Ok(())
+    }
+
+    /// Resolves `--base-ref` to a commit sha, erroring when it is not a commit in the repo
+    fn resolve_base_ref(&self, base_ref: &str) -> CliResult<String> {
+        let commit = format!("{base_ref}^{{commit}}");
+        let output = git::run(&self.repo, &["rev-parse", "--verify", "--quiet", &commit])?;
+        if !output.status.success() {
+            return Err(CliError::general_debug(
+                format!("--base-ref `{base_ref}` is not a commit in {}", self.repo),
+                git::describe_output(&format!("git rev-parse --verify {commit}"), &output),
+            ));
+        }
+        let sha = str::from_utf8(&output.stdout)
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        debug!("Resolved --base-ref {base_ref} to {sha}");
+        Ok(sha)
+    }
+
+    /// Writes the patch into the repo and applies it with the `--strategy`
+    fn apply_patch(
+        &self,
+        patch_content: &[u8],
+        version: &str,
+        sp: &mut Spinner,
+        report: &mut UpdateReport,
+    ) -> CliResult<()> {
+        let patch_path = self.repo.join(repo::PATCH_FILENAME);
+        let _partial = interrupt::remove_on_interrupt(&patch_path);
+        let (user_name, user_email) = self.commit_identity();
+        let written = if self.strategy == ApplyStrategy::Am {
+            // `git am` reads the commit author and message from mailbox headers
+            let mut mailbox =
+                format!("From: {user_name} <{user_email}>\nSubject: Update SDK ({version})\n\n")
+                    .into_bytes();
+            mailbox.extend_from_slice(patch_content);
+            fs::write(&patch_path, mailbox)
+        } else {
+            fs::write(&patch_path, patch_content)
+        };
+        written.map_err(|e| CliError::io_custom("Failed writing sdk git patch file", e))?;
+
+        // a conflicted 3-way apply is rolled back with `git reset --merge`, which would
+        // also discard changes that were staged beforehand
+        let staged_before = self.strategy == ApplyStrategy::ThreeWay
+            && git::cli_available()
+            && git::has_staged_changes(&self.repo)?;
+        let rollback_safe = match self.strategy {
+            ApplyStrategy::Am => true,
+            ApplyStrategy::ThreeWay => git::cli_available() && !staged_before,
+            ApplyStrategy::Apply => false,
+        };
+        let patch_output = self.run_apply(&user_name, &user_email)?;
+        if patch_output.success {
+            sp.stop_success(with_emoji("🚀", "Update applied!"));
+            let files = patch::parse(&String::from_utf8_lossy(patch_content));
+            info!("{}", patch::stat_summary(&files));
+            report.applied = true;
             fs::remove_file(&patch_path)?;
+            if self.strategy == ApplyStrategy::ThreeWay {
+                self.unstage_update(&files, staged_before)?;
+            }
+            if self.verify_clean_after_apply {
+                self.verify_only_patch_changes(patch_content)?;
+            }
+            if self.strategy == ApplyStrategy::Am {
+                info!("Committed update as {user_name} <{user_email}>");
+            } else if self.commit {
+                self.commit_update(version)?;
+            }
             Ok(())
-        } else {
-            sp.stop_error("Failed to apply update");
+        } else if let Some(conflicts) = self.merge_conflicts()? {
+            sp.stop_warn("Update applied with conflicts");
+            for path in &conflicts {
+                warn!("Conflict merging update: {path}");
+            }
+            let reason = if self.base_ref.is_some() {
+                "conflicts with changes made since --base-ref"
+            } else {
+                "conflicts with local changes"
+            };
+            let next_step = match self.strategy {
+                ApplyStrategy::Am => "then run `git am --continue` (or `git am --abort`)",
+                _ => "then commit",
+            };
+            report.record_merge_conflicts(&conflicts, reason);
+            fs::remove_file(&patch_path)?;
+            if self.rollback(rollback_safe)? {
+                return Err(CliError::general_debug(
+                    format!(
+                        "Update {reason} in {} file(s): {}. The update was rolled back",
+                        conflicts.len(),
+                        conflicts.join(", ")
+                    ),
+                    patch_output.details,
+                ));
+            }
             Err(CliError::general_debug(
-                "Failed to apply update",
                 format!(
-                    "`git patch` failure (exit status {exit})\nstdout:\n{stdout}\nstderr:\n{stderr}",
-                    exit = patch_output.status,
-                    stdout = str::from_utf8(&patch_output.stdout).unwrap_or_default(),
-                    stderr = str::from_utf8(&patch_output.stderr).unwrap_or_default(),
+                    "Update {reason} in {} file(s): {}. Resolve the conflict markers, {next_step}",
+                    conflicts.len(),
+                    conflicts.join(", ")
                 ),
+                patch_output.details,
             ))
+        } else {
+            sp.stop_error("Failed to apply update");
+            if self.strategy == ApplyStrategy::Am {
+                let abort_output = git::run(&self.repo, &["am", "--abort"])?;
+                debug!("{}", git::describe_output("git am --abort", &abort_output));
+            }
+            // nothing was applied, `git apply` and libgit2 leave the tree untouched when a hunk fails
+            fs::remove_file(&patch_path)?;
+            let failed = report.record_apply_conflicts(&patch_output.stderr);
+            let message = if failed.is_empty() {
+                "Failed to apply update".to_string()
+            } else {
+                format!(
+                    "Failed to apply update, it does not apply cleanly to {} file(s): {}",
+                    failed.len(),
+                    failed.join(", ")
+                )
+            };
+            Err(CliError::general_debug(message, patch_output.details))
+        }
+    }
+
+    /// Unstages the files a clean 3-way apply added to the index, so the update is left
+    /// unstaged like with the other strategies. Skipped when changes were staged before
+    /// the update, as they could not be told apart
+    fn unstage_update(&self, files: &[patch::FilePatch], staged_before: bool) -> CliResult<()> {
+        if !git::cli_available() {
+            return Ok(());
+        }
+        if staged_before {
+            warn!("Changes were staged before the update, the update is left staged with them");
+            return Ok(());
+        }
+
+        let mut args = vec!["reset", "-q", "--"];
+        args.extend(
+            files
+                .iter()
+                .flat_map(|f| [f.path.as_str(), f.old_path.as_str()]),
+        );
+        let reset_output = git::run(&self.repo, &args)?;
+        if !reset_output.status.success() {
+            return Err(CliError::general_debug(
+                "Failed to unstage the applied update",
+                git::describe_output("git reset -q", &reset_output),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Offers to undo an update that applied with conflicts, returning the repo to its
+    /// state before the update
+    ///
+    /// Returns: whether the update was rolled back
+    fn rollback(&self, safe: bool) -> CliResult<bool> {
+        if self.no_rollback || !io::stdin().is_terminal() {
+            return Ok(false);
+        }
+        if !safe {
+            warn!(
+                "Not offering to roll back the update, changes were staged before it was applied"
+            );
+            return Ok(false);
+        }
+
+        let confirmed = prompt::prompt(
+            || {
+                Confirm::new("Roll back the update and resolve the conflicts later?")
+                    .with_help_message(
+                        "restores the files changed by the update, --no-rollback skips this prompt",
+                    )
+                    .with_default(false)
+                    .prompt()
+            },
+            Some(false),
+        )?;
+        if !confirmed {
+            return Ok(false);
+        }
+
+        let (command, args): (&str, &[&str]) = match self.strategy {
+            ApplyStrategy::Am => ("git am --abort", &["am", "--abort"]),
+            _ => ("git reset --merge", &["reset", "--merge"]),
+        };
+        let rollback_output = git::run(&self.repo, args)?;
+        if !rollback_output.status.success() {
+            return Err(CliError::general_debug(
+                "Failed to roll back the update, the conflicts are still in place",
+                git::describe_output(command, &rollback_output),
+            ));
+        }
+        info!("Rolled back the update");
+        Ok(true)
+    }
+
+    /// Runs the `--strategy` on the patch written into the repo, a 3-way apply that fails
+    /// without leaving conflicts (e.g. the repo lacks the patch's base blobs, or the
+    /// working tree differs from the index) falls back to a plain apply
+    ///
+    /// 3-way merges need the `git` CLI, without it the update is applied with libgit2
+    fn run_apply(&self, user_name: &str, user_email: &str) -> CliResult<git::ApplyOutput> {
+        match self.strategy {
+            ApplyStrategy::Apply => git::apply(&self.repo, repo::PATCH_FILENAME),
+            ApplyStrategy::ThreeWay => {
+                // an update against --base-ref cannot be applied onto HEAD without a merge
+                if !git::cli_available() && self.base_ref.is_none() {
+                    warn!("`git` is not installed, applying the update without a 3-way merge");
+                    return git::apply(&self.repo, repo::PATCH_FILENAME);
+                }
+                let output = git::run(&self.repo, &["apply", "--3way", repo::PATCH_FILENAME])?;
+                if output.status.success()
+                    || self.base_ref.is_some()
+                    || self.merge_conflicts()?.is_some()
+                {
+                    return Ok(git::ApplyOutput::from_cli("git apply --3way", &output));
+                }
+                debug!("{}", git::describe_output("git apply --3way", &output));
+                info!("3-way apply failed, falling back to a plain apply");
+                git::apply(&self.repo, repo::PATCH_FILENAME)
+            }
+            ApplyStrategy::Am => {
+                let user_name = format!("user.name={user_name}");
+                let user_email = format!("user.email={user_email}");
+                let output = git::run(
+                    &self.repo,
+                    &[
+                        "-c",
+                        &user_name,
+                        "-c",
+                        &user_email,
+                        "am",
+                        "--3way",
+                        repo::PATCH_FILENAME,
+                    ],
+                )?;
+                Ok(git::ApplyOutput::from_cli("git am --3way", &output))
+            }
         }
     }
-}
 
-#[derive(Debug, serde::Deserialize)]
-struct SdkMetadata {
-    pub id: String,
+    /// Commits the applied update, any stashed changes are restored afterwards
+    /// so only the update is committed
+    fn commit_update(&self, version: &str) -> CliResult<()> {
+        let (user_name, user_email) = self.commit_identity();
+        let message = format!("Update SDK ({version})");
+        git::commit_all(&self.repo, &message, &user_name, &user_email)?;
+        info!("Committed update as {user_name} <{user_email}>");
+        Ok(())
+    }
+
+    /// Author name and email of the update commit
+    fn commit_identity(&self) -> (String, String) {
+        let user_name = self
+            .git_user_name
+            .clone()
+            .or_else(|| ConfigKey::GitUserName.get_env())
+            .unwrap_or(git::DEFAULT_USER_NAME.into());
+        let user_email = self
+            .git_user_email
+            .clone()
+            .or_else(|| ConfigKey::GitUserEmail.get_env())
+            .unwrap_or(git::DEFAULT_USER_EMAIL.into());
+        (user_name, user_email)
+    }
+
+    /// Verifies every changed path in the working tree is part of the applied patch
+    fn verify_only_patch_changes(&self, patch_content: &[u8]) -> CliResult<()> {
+        let expected: HashSet<String> = patch::parse(&String::from_utf8_lossy(patch_content))
+            .into_iter()
+            .flat_map(|f| [f.path, f.old_path])
+            .collect();
+
+        let unexpected: Vec<String> = git::changed_files(&self.repo)?
+            .into_iter()
+            .filter(|p| !expected.contains(p))
+            .collect();
+
+        if unexpected.is_empty() {
+            debug!("Working tree only contains changes from the update");
+            return Ok(());
+        }
+
+        for path in &unexpected {
+            warn!("Unexpected change after applying update: {path}");
+        }
+        Err(CliError::general(format!(
+            "Working tree contains {} change(s) that are not part of the update",
+            unexpected.len()
+        )))
+    }
+
+    /// Stashes uncommitted changes (including untracked files)
+    ///
+    /// Returns: whether anything was stashed
+    fn stash_push(&self) -> CliResult<bool> {
+        let status_output = git::run(&self.repo, &["status", "--porcelain"])?;
+        if status_output.stdout.is_empty() {
+            return Ok(false);
+        }
+
+        let stash_output = git::run(
+            &self.repo,
+            &[
+                "stash",
+                "push",
+                "--include-untracked",
+                "--message",
+                "sideko sdk update autostash",
+            ],
+        )?;
+        if !stash_output.status.success() {
+            return Err(CliError::general_debug(
+                "Failed to stash uncommitted changes",
+                git::describe_output("git stash push", &stash_output),
+            ));
+        }
+        info!("Stashed uncommitted changes");
+        Ok(true)
+    }
+
+    /// Restores stashed changes, leaving conflict markers in place if they
+    /// conflict with the applied update
+    fn stash_pop(&self, report: &mut UpdateReport) -> CliResult<()> {
+        let pop_output = git::run(&self.repo, &["stash", "pop"])?;
+        if pop_output.status.success() {
+            info!("Restored stashed changes");
+            return Ok(());
+        }
+
+        let conflicts = git::conflicted_paths(&self.repo)?;
+        if conflicts.is_empty() {
+            return Err(CliError::general_debug(
+                "Failed to restore stashed changes, they are still available with `git stash list`",
+                git::describe_output("git stash pop", &pop_output),
+            ));
+        }
+
+        for path in &conflicts {
+            warn!("Conflict restoring stashed changes: {path}");
+        }
+        report.record_stash_conflicts(&conflicts);
+        Err(CliError::general_debug(
+            format!(
+                "Restoring stashed changes conflicted with the update in {} file(s). Resolve the conflict markers, then run `git stash drop`",
+                conflicts.len()
+            ),
+            git::describe_output("git stash pop", &pop_output),
+        ))
+    }
 }
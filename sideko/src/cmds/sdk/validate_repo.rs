@@ -0,0 +1,68 @@
+use camino::Utf8PathBuf;
+use log::{debug, info};
+
+use crate::{
+    result::{CliError, CliResult},
+    styles::{fmt_green, fmt_red},
+};
+
+use super::repo;
+
+#[derive(clap::Args)]
+pub struct SdkValidateRepoCommand {
+    /// Path to root of SDK repo
+    #[arg(long, value_parser = crate::utils::validators::validate_dir)]
+    pub repo: Utf8PathBuf,
+}
+
+impl SdkValidateRepoCommand {
+    pub async fn handle(&self) -> CliResult<()> {
+        let git_root = repo::validate_git_root(&self.repo).map(|_| ());
+        let git_clean = match &git_root {
+            Ok(_) => repo::validate_git_clean(&self.repo),
+            Err(_) => Err(CliError::general("Not a git repository")),
+        };
+        let checks = vec![
+            ("Git repository root", git_root),
+            ("Working directory clean", git_clean),
+            (
+                "SDK metadata (.sdk.json) has an id",
+                repo::validate_sdk_id(&self.repo).map(|id| debug!("Found SDK id: {id}")),
+            ),
+            (
+                "No leftover update files",
+                repo::leftover_patch_files(&self.repo).and_then(|leftovers| {
+                    if leftovers.is_empty() {
+                        Ok(())
+                    } else {
+                        let paths: Vec<String> = leftovers.iter().map(|p| p.to_string()).collect();
+                        Err(CliError::general(format!(
+                            "Remove leftover files from a previous update: {}",
+                            paths.join(", ")
+                        )))
+                    }
+                }),
+            ),
+        ];
+
+        let mut failed = 0;
+        for (name, result) in &checks {
+            match result {
+                Ok(_) => info!("{} {name}", fmt_green("✔")),
+                Err(e) => {
+                    failed += 1;
+                    info!("{} {name}: {}", fmt_red("✘"), e.message());
+                }
+            }
+        }
+
+        if failed > 0 {
+            return Err(CliError::general(format!(
+                "SDK repository is not ready to update, {failed} check(s) failed"
+            )));
+        }
+
+        info!("SDK repository is ready to update");
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use log::{debug, info};
+use sideko_rest_api::{
+    models::{ApiVersion, ConfigCustomizationsEnum},
+    resources::sdk::config::SyncRequest,
+    UploadFile,
+};
+
+use crate::{
+    cmds::OutputFormat,
+    result::{CliError, CliResult},
+    styles::{fmt_green, fmt_red, fmt_yellow},
+    utils::{
+        self, api_version, get_sideko_client, openapi,
+        parse_mode::{self, ParseMode},
+        sdk_config::SdkConfigSource,
+    },
+};
+
+use super::config::schema;
+
+/// Statuses of the sync endpoint rejecting the config or spec
+const VALIDATION_STATUSES: [u16; 2] = [400, 422];
+
+#[derive(clap::Args)]
+pub struct SdkValidateSpecCommand {
+    /// Path to SDK config
+    #[arg(long, value_parser = crate::utils::validators::validate_file_yaml)]
+    pub config: Utf8PathBuf,
+
+    /// API name or id e.g. my-api
+    #[arg(long)]
+    pub name: String,
+
+    /// API version to validate the config against (e.g. `2.1.5`), `auto` to use `info.version`
+    /// from the config, or `@<path>` to read it from a file
+    #[arg(
+        long,
+        value_parser = crate::utils::validators::validate_api_version,
+        default_value = "latest",
+    )]
+    pub api_version: String,
+
+    /// Validate against a local OpenAPI specification instead of an API version
+    #[arg(long, value_parser = crate::utils::validators::validate_file_json_yaml)]
+    pub spec: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ValidationIssue {
+    severity: Severity,
+    /// Where the issue was found, `local` or `server`
+    source: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<serde_json::Value>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ValidationReport {
+    valid: bool,
+    issues: Vec<ValidationIssue>,
+}
+
+impl SdkValidateSpecCommand {
+    fn local_issues(&self, config: &str) -> Vec<ValidationIssue> {
+        let message = match schema::read(config) {
+            Some(version) if version > schema::SCHEMA_VERSION => format!(
+                "Config schema version {version} is newer than the version supported by this CLI ({})",
+                schema::SCHEMA_VERSION
+            ),
+            Some(version) if version < schema::SCHEMA_VERSION => format!(
+                "Config schema version {version} is older than the version supported by this CLI ({}), run `sideko sdk config sync` to migrate",
+                schema::SCHEMA_VERSION
+            ),
+            _ => return vec![],
+        };
+
+        vec![ValidationIssue {
+            severity: Severity::Warning,
+            source: "local",
+            message,
+            details: None,
+        }]
+    }
+
+    /// Duplicate keys in the config and spec, and unknown top-level spec fields,
+    /// which are errors with `--parse-strict`
+    fn parse_issues(&self, config: &str) -> CliResult<Vec<ValidationIssue>> {
+        let severity = match ParseMode::current() {
+            ParseMode::Strict => Severity::Error,
+            ParseMode::Lenient => Severity::Warning,
+        };
+        let mut messages: Vec<String> = parse_mode::duplicate_keys(config, false)
+            .into_iter()
+            .map(|issue| format!("SDK config: {issue}"))
+            .collect();
+        if let Some(spec) = &self.spec {
+            let (_, spec_issues) = openapi::load_with_issues(spec)?;
+            messages.extend(
+                spec_issues
+                    .into_iter()
+                    .map(|issue| format!("OpenAPI: {issue}")),
+            );
+        }
+
+        Ok(messages
+            .into_iter()
+            .map(|message| ValidationIssue {
+                severity: severity.clone(),
+                source: "local",
+                message,
+                details: None,
+            })
+            .collect())
+    }
+
+    /// Validates the config server-side by syncing it with the API without
+    /// generating an SDK or writing the synced config
+    async fn server_issues(&self, config: &str) -> CliResult<Vec<ValidationIssue>> {
+        let (api_version, openapi) = if let Some(spec) = &self.spec {
+            (
+                None,
+                Some(UploadFile::from_path(spec.as_str()).map_err(|e| {
+                    CliError::io_custom(format!("Failed reading OpenAPI from path: {spec}"), e)
+                })?),
+            )
+        } else {
+            let requested = if self.api_version == api_version::AUTO {
+                api_version::from_config_info(
+                    &SdkConfigSource::File(self.config.clone()).load()?,
+                    &self.config,
+                )?
+            } else {
+                self.api_version.clone()
+            };
+            let version = api_version::resolve_api_version(&self.name, &requested, false).await?;
+            (Some(ApiVersion::Str(version)), None)
+        };
+
+        let synced = get_sideko_client()
+            .await?
+            .sdk()
+            .config()
+            .sync(SyncRequest {
+                api_version,
+                config: UploadFile::from_path(self.config.as_str()).map_err(|e| {
+                    CliError::io_custom(
+                        format!("Failed reading config from path: {}", &self.config),
+                        e,
+                    )
+                })?,
+                customizations: Some(ConfigCustomizationsEnum::Config),
+                openapi,
+            })
+            .await;
+
+        let synced = match synced {
+            Ok(synced) => synced,
+            // only rejections of the config itself are issues, other failures
+            // (auth, network, timeouts) mean the config could not be validated
+            Err(sideko_rest_api::Error::Api(e))
+                if VALIDATION_STATUSES.contains(&e.status_code.as_u16()) =>
+            {
+                let e = CliError::from(sideko_rest_api::Error::Api(e));
+                return Ok(vec![ValidationIssue {
+                    severity: Severity::Error,
+                    source: "server",
+                    message: e.message(),
+                    details: e.details(),
+                }]);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // the server fills in modules/functions missing from the config, any
+        // difference means the config no longer matches the API
+        let parse = |content: &str, source: &str| {
+            serde_yaml::from_str::<serde_yaml::Value>(content).map_err(|e| {
+                CliError::general_debug(format!("Failed parsing {source}"), format!("{e:?}"))
+            })
+        };
+        let synced = parse(&String::from_utf8_lossy(&synced.content), "synced config")?;
+        if synced != parse(config, &format!("config from path: {}", self.config))? {
+            debug!("Synced config differs from {}", self.config);
+            return Ok(vec![ValidationIssue {
+                severity: Severity::Warning,
+                source: "server",
+                message:
+                    "Config is out of sync with the API, run `sideko sdk config sync` to update it"
+                        .into(),
+                details: None,
+            }]);
+        }
+
+        Ok(vec![])
+    }
+
+    pub async fn handle(&self) -> CliResult<()> {
+        let config = fs::read_to_string(&self.config).map_err(|e| {
+            CliError::io_custom(
+                format!("Failed reading config from path: {}", self.config),
+                e,
+            )
+        })?;
+
+        let mut issues = self.local_issues(&config);
+        issues.extend(self.parse_issues(&config)?);
+        issues.extend(self.server_issues(&config).await?);
+        let report = ValidationReport {
+            valid: issues.iter().all(|i| i.severity != Severity::Error),
+            issues,
+        };
+
+        match OutputFormat::current() {
+            OutputFormat::Json => utils::logging::print_json(&report),
+            OutputFormat::Yaml => utils::logging::print_yaml(&report),
+            OutputFormat::Text => {
+                for issue in &report.issues {
+                    let symbol = match issue.severity {
+                        Severity::Warning => fmt_yellow("ø"),
+                        Severity::Error => fmt_red("✘"),
+                    };
+                    info!("{symbol} [{}] {}", issue.source, issue.message);
+                }
+                if report.issues.is_empty() {
+                    info!("{} {} is valid", fmt_green("✔"), self.config);
+                }
+            }
+        }
+
+        if report.valid {
+            Ok(())
+        } else {
+            Err(CliError::general(format!(
+                "SDK config {} is not valid",
+                self.config
+            )))
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use log::info;
+
+use crate::{
+    cmds::OutputFormat,
+    result::{CliError, CliResult},
+    styles::fmt_green,
+    utils::{self, get_sideko_client},
+};
+
+#[derive(clap::Args)]
+pub(crate) struct WhoamiCommand {}
+
+#[derive(Debug, serde::Serialize)]
+struct Whoami {
+    authenticated: bool,
+    api_key_source: Option<String>,
+    org: Option<String>,
+    base_url: String,
+}
+
+impl WhoamiCommand {
+    pub async fn handle(&self) -> CliResult<()> {
+        let mut whoami = Whoami {
+            authenticated: false,
+            api_key_source: utils::config::get_api_key_with_source()
+                .map(|(_, source)| source.to_string()),
+            org: None,
+            base_url: utils::config::get_base_url()?,
+        };
+
+        let org_res = if whoami.api_key_source.is_some() {
+            get_sideko_client()
+                .await?
+                .org()
+                .get()
+                .await
+                .map_err(CliError::from)
+        } else {
+            Err(CliError::general(
+                "CLI is not authenticated, run `sideko login`",
+            ))
+        };
+        if let Ok(org) = &org_res {
+            whoami.authenticated = true;
+            whoami.org = Some(org.subdomain.clone());
+        }
+
+        match OutputFormat::current() {
+            OutputFormat::Json => utils::logging::print_json(&whoami),
+            OutputFormat::Yaml => utils::logging::print_yaml(&whoami),
+            OutputFormat::Text => {
+                if let Some(org) = &whoami.org {
+                    info!("{} Authenticated to organization `{org}`", fmt_green("✔"));
+                }
+                if let Some(source) = &whoami.api_key_source {
+                    info!("API key source: {source}");
+                }
+                info!("API base url: {}", whoami.base_url);
+            }
+        }
+
+        org_res.map(|_| ())
+    }
+}
@@ -2,9 +2,8 @@
 async fn main() {
     let res = sideko::cli::cli(std::env::args().collect()).await;
 
-    if res.is_err() {
-        std::process::exit(1)
-    } else {
-        std::process::exit(0)
+    match res {
+        Ok(_) => std::process::exit(0),
+        Err(e) => std::process::exit(e.exit_code()),
     }
 }
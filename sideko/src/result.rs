@@ -1,4 +1,6 @@
-use std::io;
+use std::{fs, io, io::Write};
+
+use camino::{Utf8Path, Utf8PathBuf};
 
 use log::{debug, error};
 
@@ -24,6 +26,12 @@ pub enum CliError {
         err: keyring::Error,
         override_msg: Option<String>,
     },
+    /// Output location already has content and must not be overwritten
+    OutputExists {
+        path: Utf8PathBuf,
+    },
+    /// Command was interrupted (Ctrl-C) before completing
+    Interrupted,
 }
 
 impl CliError {
@@ -65,65 +73,184 @@ impl CliError {
         }
     }
 
-    pub fn log(&self) {
-        let err_log = match self {
-            CliError::General { msg, debug } => {
-                if let Some(d) = debug {
-                    debug!("{d}")
-                }
-                msg.clone()
-            }
+    /// Stable, machine-readable error code used in JSON error output
+    ///
+    /// - `general_error`: CLI usage or validation failure
+    /// - `io_error`: filesystem or process failure
+    /// - `api_error`: Sideko API request failure
+    /// - `prompt_error`: interactive prompt failure
+    /// - `keyring_error`: OS-native key storage failure
+    /// - `output_exists`: output location is not empty
+    /// - `interrupted`: command was interrupted
+    pub fn code(&self) -> &'static str {
+        match self {
+            CliError::General { .. } => "general_error",
+            CliError::Io { .. } => "io_error",
+            CliError::Api { .. } => "api_error",
+            CliError::Inquire { .. } => "prompt_error",
+            CliError::Keyring { .. } => "keyring_error",
+            CliError::OutputExists { .. } => "output_exists",
+            CliError::Interrupted => "interrupted",
+        }
+    }
+
+    /// Process exit code for the error, `1` unless the failure needs to be
+    /// distinguishable by scripts (clap reserves `2` for usage errors)
+    ///
+    /// - `3`: output location is not empty
+    /// - `130`: interrupted (conventional `128 + SIGINT`)
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::OutputExists { .. } => 3,
+            CliError::Interrupted => 130,
+            _ => 1,
+        }
+    }
+
+    /// Human-readable error message
+    pub fn message(&self) -> String {
+        match self {
+            CliError::General { msg, .. } => msg.clone(),
             CliError::Io { override_msg, err } => {
-                debug!("{err:?}");
                 override_msg.clone().unwrap_or_else(|| err.to_string())
             }
             CliError::Keyring { override_msg, err } => {
-                debug!("{err:?}");
                 override_msg.clone().unwrap_or_else(|| err.to_string())
             }
-            CliError::Inquire { err, override_msg } => {
-                debug!("{err:?}");
+            CliError::Inquire { override_msg, err } => {
                 override_msg.clone().unwrap_or_else(|| err.to_string())
             }
             CliError::Api { override_msg, err } => {
-                match err {
-                    sideko_rest_api::Error::Io(e) => debug!("SDK IO Error: {e:?}"),
-                    sideko_rest_api::Error::Request(e) => debug!("SDK Request Error: {e:?}"),
-                    sideko_rest_api::Error::DeserializeJson(e, json_str) => {
-                        let res_json = serde_json::to_string_pretty(
-                            &serde_json::from_str::<serde_json::Value>(json_str)
-                                .unwrap_or_default(),
-                        )
-                        .unwrap_or_else(|_| json_str.to_string());
-                        debug!("Deserializer Error: {e:?}");
-                        debug!("Raw JSON: {res_json}");
-                    }
-                    sideko_rest_api::Error::Api(e) | sideko_rest_api::Error::ContentType(e) => {
-                        debug!("Response headers: {:#?}", &e.headers);
-                        if let Ok(val) = e.json::<serde_json::Value>() {
-                            log::debug!(
-                                "Body: {}",
-                                serde_json::to_string_pretty(&val)
-                                    .unwrap_or_else(|_| val.to_string())
-                            );
-                            if let Some(serde_json::Value::String(description)) =
-                                val.get("description")
-                            {
-                                error!("{description}");
-                            }
-                        } else if let Ok(text) = std::str::from_utf8(&e.content) {
-                            log::debug!("Body: {text}",);
-                        } else {
-                            log::debug!("Unable to display body ({} bytes)", e.content.len())
-                        }
+                override_msg.clone().unwrap_or_else(|| err.to_string())
+            }
+            CliError::OutputExists { path } => {
+                format!("Output {path} already exists and is not empty")
+            }
+            CliError::Interrupted => "Interrupted".into(),
+        }
+    }
+
+    /// Additional error context, typically only shown in verbose mode
+    pub fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            CliError::General { debug, .. } => debug.clone().map(serde_json::Value::String),
+            CliError::OutputExists { .. } | CliError::Interrupted => None,
+            CliError::Io { err, .. } => Some(format!("{err:?}").into()),
+            CliError::Keyring { err, .. } => Some(format!("{err:?}").into()),
+            CliError::Inquire { err, .. } => Some(format!("{err:?}").into()),
+            CliError::Api { err, .. } => match err {
+                sideko_rest_api::Error::Api(e) | sideko_rest_api::Error::ContentType(e) => {
+                    if let Ok(val) = e.json::<serde_json::Value>() {
+                        Some(val)
+                    } else {
+                        std::str::from_utf8(&e.content)
+                            .ok()
+                            .map(|text| text.to_string().into())
                     }
                 }
+                _ => Some(format!("{err:?}").into()),
+            },
+        }
+    }
 
-                override_msg.clone().unwrap_or_else(|| err.to_string())
+    /// Request id of the failed Sideko API request (if available)
+    pub fn request_id(&self) -> Option<String> {
+        if let CliError::Api {
+            err: sideko_rest_api::Error::Api(e) | sideko_rest_api::Error::ContentType(e),
+            ..
+        } = self
+        {
+            e.headers
+                .get("x-request-id")
+                .and_then(|v| v.to_str().ok())
+                .map(String::from)
+        } else {
+            None
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "code": self.code(),
+            "message": self.message(),
+            "details": self.details(),
+            "request_id": self.request_id(),
+        })
+    }
+
+    /// Appends the error as a single line of JSON to the error log at `path`
+    pub fn append_to_log(&self, path: &Utf8Path, command: &str) -> io::Result<()> {
+        let record = serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "command": command,
+            "variant": self.code(),
+            "message": self.message(),
+            "request_id": self.request_id(),
+        });
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{record}")
+    }
+
+    /// Writes the error as a single line of JSON to stderr
+    pub fn log_json(&self) {
+        eprintln!("{}", self.to_json());
+    }
+
+    /// Writes the error as a YAML document to stderr
+    pub fn log_yaml(&self) {
+        match serde_yaml::to_string(&self.to_json()) {
+            Ok(yaml) => eprint!("{yaml}"),
+            Err(_) => self.log_json(),
+        }
+    }
+
+    pub fn log(&self) {
+        match self {
+            CliError::General { debug, .. } => {
+                if let Some(d) = debug {
+                    debug!("{d}")
+                }
             }
-        };
+            CliError::Io { err, .. } => debug!("{err:?}"),
+            CliError::Keyring { err, .. } => debug!("{err:?}"),
+            CliError::Inquire { err, .. } => debug!("{err:?}"),
+            CliError::OutputExists { .. } | CliError::Interrupted => {}
+            CliError::Api { err, .. } => match err {
+                sideko_rest_api::Error::Io(e) => debug!("SDK IO Error: {e:?}"),
+                sideko_rest_api::Error::Request(e) => debug!("SDK Request Error: {e:?}"),
+                sideko_rest_api::Error::DeserializeJson(e, json_str) => {
+                    let res_json = serde_json::to_string_pretty(
+                        &serde_json::from_str::<serde_json::Value>(json_str).unwrap_or_default(),
+                    )
+                    .unwrap_or_else(|_| json_str.to_string());
+                    debug!("Deserializer Error: {e:?}");
+                    debug!("Raw JSON: {res_json}");
+                }
+                sideko_rest_api::Error::Api(e) | sideko_rest_api::Error::ContentType(e) => {
+                    debug!("Response headers: {:#?}", &e.headers);
+                    if let Ok(val) = e.json::<serde_json::Value>() {
+                        log::debug!(
+                            "Body: {}",
+                            serde_json::to_string_pretty(&val).unwrap_or_else(|_| val.to_string())
+                        );
+                        if let Some(serde_json::Value::String(description)) = val.get("description")
+                        {
+                            error!("{description}");
+                        }
+                    } else if let Ok(text) = std::str::from_utf8(&e.content) {
+                        log::debug!("Body: {text}",);
+                    } else {
+                        log::debug!("Unable to display body ({} bytes)", e.content.len())
+                    }
+                }
+            },
+        }
 
-        error!("{err_log}");
+        error!("{}", self.message());
     }
 }
 
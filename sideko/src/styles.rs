@@ -1,7 +1,82 @@
 use anstyle::{AnsiColor, Color, Style};
 use clap::builder::Styles;
 
+use crate::utils::config::ConfigKey;
+
+/// Palette used for styled output (status symbols, tables, help)
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorTheme {
+    /// Green/yellow/red status colors
+    #[default]
+    Default,
+    /// Bright blue/yellow/magenta status colors, distinguishable with common color vision deficiencies
+    HighContrast,
+    /// No colors
+    Mono,
+}
+impl ColorTheme {
+    /// Theme selected via `--color-theme` or `SIDEKO_COLOR_THEME`, `mono` when
+    /// `NO_COLOR` is set (https://no-color.org)
+    pub fn current() -> Self {
+        if let Some(theme) = ConfigKey::ColorTheme.get_env() {
+            if let Ok(theme) = <Self as clap::ValueEnum>::from_str(theme.trim(), true) {
+                return theme;
+            }
+        }
+        if std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty()) {
+            return ColorTheme::Mono;
+        }
+        ColorTheme::Default
+    }
+
+    fn success(&self) -> Option<AnsiColor> {
+        match self {
+            ColorTheme::Default => Some(AnsiColor::Green),
+            ColorTheme::HighContrast => Some(AnsiColor::BrightBlue),
+            ColorTheme::Mono => None,
+        }
+    }
+
+    fn warning(&self) -> Option<AnsiColor> {
+        match self {
+            ColorTheme::Default => Some(AnsiColor::Yellow),
+            ColorTheme::HighContrast => Some(AnsiColor::BrightYellow),
+            ColorTheme::Mono => None,
+        }
+    }
+
+    fn error(&self) -> Option<AnsiColor> {
+        match self {
+            ColorTheme::Default => Some(AnsiColor::Red),
+            ColorTheme::HighContrast => Some(AnsiColor::BrightMagenta),
+            ColorTheme::Mono => None,
+        }
+    }
+
+    fn accent(&self) -> Option<AnsiColor> {
+        match self {
+            ColorTheme::Default | ColorTheme::HighContrast => Some(AnsiColor::BrightCyan),
+            ColorTheme::Mono => None,
+        }
+    }
+
+    /// Color of the progress spinner, `None` for uncolored
+    pub fn spinner_color(&self) -> Option<spinoff::Color> {
+        match self {
+            ColorTheme::Default => Some(spinoff::Color::Cyan),
+            ColorTheme::HighContrast => Some(spinoff::Color::White),
+            ColorTheme::Mono => None,
+        }
+    }
+}
+
 pub fn get_styles() -> Styles {
+    let theme = ColorTheme::current();
+    if theme == ColorTheme::Mono {
+        return Styles::plain();
+    }
+    let color = |c: Option<AnsiColor>| c.map(Color::Ansi);
+
     Styles::styled()
         .usage(
             Style::new()
@@ -15,46 +90,68 @@ pub fn get_styles() -> Styles {
                 .underline()
                 .fg_color(Some(Color::Ansi(AnsiColor::Blue))),
         )
-        .literal(Style::new().fg_color(Some(Color::Ansi(AnsiColor::Green))))
-        .invalid(
-            Style::new()
-                .bold()
-                .fg_color(Some(Color::Ansi(AnsiColor::Red))),
-        )
-        .error(
-            Style::new()
-                .bold()
-                .fg_color(Some(Color::Ansi(AnsiColor::Red))),
-        )
+        .literal(Style::new().fg_color(color(theme.success())))
+        .invalid(Style::new().bold().fg_color(color(theme.error())))
+        .error(Style::new().bold().fg_color(color(theme.error())))
         .valid(
             Style::new()
                 .bold()
                 .underline()
-                .fg_color(Some(Color::Ansi(AnsiColor::Green))),
+                .fg_color(color(theme.success())),
         )
         .placeholder(Style::new().fg_color(Some(Color::Ansi(AnsiColor::White))))
 }
 
-fn fmt_style(msg: &str, style: &Style) -> String {
-    format!("{style}{msg}{style:#}")
+fn fmt_style(msg: &str, color: Option<AnsiColor>) -> String {
+    match color {
+        Some(color) => {
+            let style = Style::new().fg_color(Some(Color::Ansi(color)));
+            format!("{style}{msg}{style:#}")
+        }
+        None => msg.into(),
+    }
 }
 
+/// Formats as success, green in the default theme
 pub fn fmt_green(msg: &str) -> String {
-    let green = Style::new().fg_color(Some(Color::Ansi(AnsiColor::Green)));
-    fmt_style(msg, &green)
+    fmt_style(msg, ColorTheme::current().success())
 }
 
 pub fn fmt_cyan(msg: &str) -> String {
-    let cyan = Style::new().fg_color(Some(Color::Ansi(AnsiColor::BrightCyan)));
-    fmt_style(msg, &cyan)
+    fmt_style(msg, ColorTheme::current().accent())
 }
 
+/// Formats as an error, red in the default theme
 pub fn fmt_red(msg: &str) -> String {
-    let red = Style::new().fg_color(Some(Color::Ansi(AnsiColor::Red)));
-    fmt_style(msg, &red)
+    fmt_style(msg, ColorTheme::current().error())
 }
 
+/// Formats as a warning, yellow in the default theme
 pub fn fmt_yellow(msg: &str) -> String {
-    let yellow = Style::new().fg_color(Some(Color::Ansi(AnsiColor::Yellow)));
-    fmt_style(msg, &yellow)
+    fmt_style(msg, ColorTheme::current().warning())
+}
+
+/// Whether emoji are disabled via `--no-emoji` or `SIDEKO_NO_EMOJI`
+pub fn emoji_disabled() -> bool {
+    ConfigKey::NoEmoji
+        .get_env()
+        .is_some_and(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false"))
+}
+
+/// Returns the emoji, or the fallback when emoji are disabled
+pub fn emoji_or(emoji: &str, fallback: &str) -> String {
+    if emoji_disabled() {
+        fallback.into()
+    } else {
+        emoji.into()
+    }
+}
+
+/// Prefixes the message with the emoji unless emoji are disabled
+pub fn with_emoji(emoji: &str, msg: &str) -> String {
+    if emoji_disabled() {
+        msg.into()
+    } else {
+        format!("{emoji} {msg}")
+    }
 }
@@ -0,0 +1,103 @@
+use std::fmt::Display;
+
+use log::debug;
+use sideko_rest_api::resources::api::spec::ListRequest;
+
+use crate::result::{CliError, CliResult};
+
+use super::get_sideko_client;
+
+/// `--api-version` value resolved from the `info.version` of the config
+pub const AUTO: &str = "auto";
+
+/// Reads the API version for `--api-version auto` from the `info.version` of the config
+pub fn from_config_info(
+    config: &serde_yaml::Value,
+    config_path: impl Display,
+) -> CliResult<String> {
+    let version = config
+        .get("info")
+        .and_then(|info| info.get("version"))
+        .ok_or_else(|| {
+            CliError::general(format!(
+                "--api-version auto requires `info.version` in {config_path}, set it or pass an explicit --api-version"
+            ))
+        })?;
+    // unquoted versions like `2.1` parse as numbers
+    let version = match version {
+        serde_yaml::Value::String(v) => v.trim().to_string(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        _ => {
+            return Err(CliError::general(format!(
+                "`info.version` in {config_path} must be a string"
+            )))
+        }
+    };
+
+    if semver::Version::parse(&version).is_err() {
+        return Err(CliError::general(format!(
+            "`info.version` in {config_path} is `{version}`, which is not a semantic version (e.g. `2.1.5`)"
+        )));
+    }
+    debug!("Resolved --api-version auto to {version} from {config_path}");
+    Ok(version)
+}
+
+/// Resolves the `latest` API version to the most recent stable (non-prerelease)
+/// version of the API. Any other version, or `latest` when prereleases are allowed,
+/// is returned as-is and resolved by the Sideko API.
+pub async fn resolve_api_version(
+    api_name: &str,
+    api_version: &str,
+    allow_prerelease: bool,
+) -> CliResult<String> {
+    if api_version != "latest" || allow_prerelease {
+        return Ok(api_version.into());
+    }
+
+    let mut client = get_sideko_client().await?;
+    let versions = client
+        .api()
+        .spec()
+        .list(ListRequest {
+            api_name: api_name.into(),
+        })
+        .await?;
+
+    let latest_stable = versions
+        .iter()
+        .filter_map(|v| semver::Version::parse(&v.version).ok())
+        .filter(|v| v.pre.is_empty())
+        .max();
+
+    match latest_stable {
+        Some(version) => {
+            debug!("Resolved `latest` version of `{api_name}` to stable version {version}");
+            Ok(version.to_string())
+        }
+        None => Err(CliError::general_debug(
+            format!("No stable version found for `{api_name}`, use --allow-prerelease to include prerelease versions"),
+            format!(
+                "Available versions: {:?}",
+                versions.iter().map(|v| &v.version).collect::<Vec<_>>()
+            ),
+        )),
+    }
+}
+
+/// Resolves the `--api-version` of a command generating from an SDK config, which only
+/// knows the API with `--api-name`. `latest` needs the API name to only consider stable
+/// versions, unless prereleases are allowed and the Sideko API may resolve it
+pub async fn resolve_config_api_version(
+    api_name: Option<&str>,
+    api_version: &str,
+    allow_prerelease: bool,
+) -> CliResult<String> {
+    match api_name {
+        Some(api_name) => resolve_api_version(api_name, api_version, allow_prerelease).await,
+        None if api_version == "latest" && !allow_prerelease => Err(CliError::general(
+            "Resolving the latest stable API version requires --api-name, or pass an exact --api-version (or `auto`), or --allow-prerelease to let the Sideko API resolve `latest`",
+        )),
+        None => Ok(api_version.into()),
+    }
+}
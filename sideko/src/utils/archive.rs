@@ -0,0 +1,747 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    error::Error,
+    ffi::OsString,
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use flate2::read::GzDecoder;
+use log::{debug, warn};
+use sha2::{Digest, Sha256};
+use tar::Archive;
+
+use crate::result::{CliError, CliResult};
+
+/// Leading bytes of any gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Max characters of a non-gzip body shown in the error
+const PREVIEW_LEN: usize = 500;
+
+/// Max entries listed per directory by `file_tree`
+const TREE_MAX_ENTRIES: usize = 20;
+
+/// PAX header keys carrying the hex sha256 digest of an entry
+const PAX_SHA256_KEYS: [&str; 2] = ["SIDEKO.sha256", "sha256"];
+
+/// How symlink entries in an archive are unpacked
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+pub enum SymlinkMode {
+    /// Recreate symlinks as symlinks
+    Preserve,
+    /// Replace symlinks with a copy of the file they point to
+    Copy,
+    /// Do not unpack symlinks
+    Skip,
+}
+impl SymlinkMode {
+    /// `copy` on Windows where creating symlinks usually requires elevated privileges,
+    /// `preserve` everywhere else
+    pub fn platform_default() -> Self {
+        if cfg!(target_os = "windows") {
+            SymlinkMode::Copy
+        } else {
+            SymlinkMode::Preserve
+        }
+    }
+}
+
+/// Unpacks gzipped tar archives (e.g. generated SDKs) into a destination directory
+pub struct Unpacker {
+    dest: Utf8PathBuf,
+    symlinks: SymlinkMode,
+    strip_components: usize,
+    exclude: Vec<glob::Pattern>,
+}
+impl Unpacker {
+    pub fn new(dest: &Utf8Path) -> Self {
+        Self {
+            dest: dest.into(),
+            symlinks: SymlinkMode::platform_default(),
+            strip_components: 0,
+            exclude: vec![],
+        }
+    }
+    pub fn with_symlinks(mut self, symlinks: SymlinkMode) -> Self {
+        self.symlinks = symlinks;
+        self
+    }
+    /// Strips the leading `n` path components from each entry, entries with
+    /// `n` or fewer components are skipped (mirrors `tar --strip-components`)
+    pub fn with_strip_components(mut self, n: usize) -> Self {
+        self.strip_components = n;
+        self
+    }
+    /// Skips entries whose (stripped) path matches any of the patterns
+    pub fn with_exclude(mut self, exclude: Vec<glob::Pattern>) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    /// Path of the entry relative to the destination after stripping components,
+    /// `None` if the entry should not be unpacked
+    fn relative_path(&self, path: &Path) -> Option<PathBuf> {
+        let relative: PathBuf = path
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .skip(self.strip_components)
+            .collect();
+        if relative.as_os_str().is_empty() {
+            return None;
+        }
+
+        if self.exclude.iter().any(|p| p.matches_path(&relative)) {
+            debug!("Excluding {}", relative.display());
+            return None;
+        }
+
+        Some(relative)
+    }
+
+    /// Unpacks the archive, removing anything it wrote into the destination if
+    /// unpacking fails part way (e.g. the disk fills up)
+    ///
+    /// Returns: the unpacked file paths relative to the destination
+    pub fn unpack<R: Read>(&self, reader: R) -> CliResult<Vec<PathBuf>> {
+        let dest_existed = self.dest.exists();
+        fs::create_dir_all(&self.dest).map_err(|e| {
+            CliError::io_custom(format!("Failed creating output directory {}", self.dest), e)
+        })?;
+        let dest = self
+            .dest
+            .canonicalize()
+            .unwrap_or(self.dest.clone().into_std_path_buf());
+        let existing = top_level_entries(&dest);
+
+        let res = self.unpack_into(reader, &dest);
+        if res.is_err() {
+            cleanup(&dest, dest_existed, &existing);
+        }
+        res
+    }
+
+    fn unpack_into<R: Read>(&self, reader: R, dest: &Path) -> CliResult<Vec<PathBuf>> {
+        // non-gzip content is most likely an error body that slipped past status checks,
+        // surface it instead of an obscure decompression failure
+        let mut reader = BufReader::new(reader);
+        let head = reader
+            .fill_buf()
+            .map_err(|e| CliError::io_custom("Failed reading sdk archive", e))?;
+        if !head.starts_with(&GZIP_MAGIC) {
+            return Err(not_gzip_error(head));
+        }
+
+        let mut archive = Archive::new(GzDecoder::new(reader));
+        // permissions are unpacked without the setuid, setgid, and sticky bits,
+        // generated scripts (e.g. `gradlew`) are kept executable by `ensure_executable`
+        archive.set_preserve_mtime(true);
+        let entries = archive
+            .entries()
+            .map_err(|e| CliError::io_custom("Failed reading sdk archive", e))?;
+
+        // symlink targets may come after the link in the archive,
+        // so copies are made once all other entries are unpacked
+        let mut symlink_copies: Vec<(PathBuf, PathBuf)> = vec![];
+        let mut unpacked_files = vec![];
+        for entry in entries {
+            let mut entry =
+                entry.map_err(|e| CliError::io_custom("Failed reading sdk archive entry", e))?;
+            let path = entry
+                .path()
+                .map_err(|e| CliError::io_custom("Invalid path in sdk archive", e))?
+                .to_path_buf();
+            let Some(path) = self.relative_path(&path) else {
+                continue;
+            };
+            let expected_sha256 = if entry.header().entry_type().is_file() {
+                pax_sha256(&mut entry)?
+            } else {
+                None
+            };
+            let expected_size = entry.size();
+            let mode = entry.header().mode().ok();
+
+            if entry.header().entry_type().is_symlink() {
+                match self.symlinks {
+                    SymlinkMode::Preserve => {}
+                    SymlinkMode::Skip => {
+                        debug!("Skipping symlink {}", path.display());
+                        continue;
+                    }
+                    SymlinkMode::Copy => {
+                        if let Some(target) = entry.link_name().map_err(|e| {
+                            CliError::io_custom("Invalid symlink target in sdk archive", e)
+                        })? {
+                            symlink_copies.push((path, target.to_path_buf()));
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            let unpacked = if self.strip_components == 0 {
+                entry.unpack_in(dest).map(|_| ())
+            } else {
+                // unpack_in only guards the original entry path, so the stripped
+                // path (and any link it is written through) is guarded here
+                let Some(target) = stripped_target(&mut entry, &path, dest)? else {
+                    continue;
+                };
+                entry.unpack(&target).map(|_| ()).and_then(|_| {
+                    if entry.header().entry_type().is_symlink() {
+                        remove_escaping_symlink(&target, &path, dest)?;
+                    }
+                    Ok(())
+                })
+            };
+            unpacked.map_err(|e| write_error(&path, e))?;
+            if let (Some(mode), true) = (mode, entry.header().entry_type().is_file()) {
+                ensure_executable(&dest.join(&path), mode)?;
+            }
+
+            if let Some(expected_sha256) = expected_sha256 {
+                verify_entry(&dest.join(&path), &path, expected_size, &expected_sha256)?;
+            }
+            if !entry.header().entry_type().is_dir() {
+                unpacked_files.push(path);
+            }
+        }
+
+        // read to the end of the gzip stream so its trailing crc32/size
+        // verifies the whole archive, including entries without checksums
+        io::copy(&mut archive.into_inner(), &mut io::sink())
+            .map_err(|e| CliError::io_custom("SDK archive failed integrity check", e))?;
+
+        for (link, target) in symlink_copies {
+            if !is_safe_relative(&link) {
+                warn!("Skipping symlink with unsafe path {}", link.display());
+                continue;
+            }
+            let link_path = dest.join(&link);
+            let target_path = link_path.parent().unwrap_or(dest).join(&target);
+            let resolved = match target_path.canonicalize() {
+                Ok(p) if p.starts_with(dest) && p.is_file() => p,
+                _ => {
+                    warn!(
+                        "Skipping symlink {} -> {}: target is not a file within the output",
+                        link.display(),
+                        target.display()
+                    );
+                    continue;
+                }
+            };
+
+            if let Some(parent) = link_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| write_error(parent, e))?;
+            }
+            fs::copy(&resolved, &link_path).map_err(|e| write_error(&link, e))?;
+            debug!("Copied symlink {} -> {}", link.display(), target.display());
+            unpacked_files.push(link);
+        }
+
+        Ok(unpacked_files)
+    }
+}
+
+/// Appends the contents of `dir` to the root of the tar, skipping entries whose path
+/// relative to `dir` matches any of the patterns (along with everything under them)
+pub fn append_dir_filtered<W: Write>(
+    tar: &mut tar::Builder<W>,
+    dir: &Utf8Path,
+    exclude: &[glob::Pattern],
+) -> CliResult<()> {
+    if exclude.is_empty() {
+        tar.append_dir_all(".", dir)?;
+        return Ok(());
+    }
+
+    let mut excluded = 0;
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in current
+            .read_dir_utf8()
+            .map_err(|e| CliError::io_custom(format!("Failed reading directory {current}"), e))?
+        {
+            let path = entry?.into_path();
+            let relative = path.strip_prefix(dir).unwrap_or(&path);
+            if exclude.iter().any(|p| p.matches(relative.as_str())) {
+                debug!("Excluding {relative} from archive");
+                excluded += 1;
+                continue;
+            }
+
+            if path.is_dir() {
+                tar.append_dir(relative, &path)?;
+                pending.push(path);
+            } else {
+                tar.append_path_with_name(&path, relative)?;
+            }
+        }
+    }
+    debug!("Excluded {excluded} entries of {dir} from archive");
+
+    Ok(())
+}
+
+/// Compact tree of unpacked files: each top-level directory with its direct
+/// children, nested directories summarized by their file count
+pub fn file_tree(files: &[PathBuf]) -> String {
+    /// Direct children of a top-level directory, `None` for files and the
+    /// file count for directories
+    #[derive(Default)]
+    struct TopLevelDir {
+        files: usize,
+        children: BTreeMap<String, Option<usize>>,
+    }
+
+    let files_label = |n: usize| {
+        if n == 1 {
+            "1 file".to_string()
+        } else {
+            format!("{n} files")
+        }
+    };
+
+    let mut dirs: BTreeMap<String, TopLevelDir> = BTreeMap::new();
+    let mut top_level_files = vec![];
+    for path in files {
+        let mut components = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string());
+        let Some(top) = components.next() else {
+            continue;
+        };
+        let Some(child) = components.next() else {
+            top_level_files.push(top);
+            continue;
+        };
+
+        let dir = dirs.entry(top).or_default();
+        dir.files += 1;
+        let nested = components.next().is_some();
+        let count = dir.children.entry(child).or_insert(nested.then_some(0));
+        if let (true, Some(count)) = (nested, count) {
+            *count += 1;
+        }
+    }
+
+    let mut lines = vec![];
+    for (name, dir) in &dirs {
+        lines.push(format!("{name}/ ({})", files_label(dir.files)));
+        let hidden = dir.children.len().saturating_sub(TREE_MAX_ENTRIES);
+        let shown = dir.children.len() - hidden;
+        for (idx, (child, count)) in dir.children.iter().take(shown).enumerate() {
+            let connector = if idx + 1 == shown && hidden == 0 {
+                "└──"
+            } else {
+                "├──"
+            };
+            match count {
+                Some(count) => {
+                    lines.push(format!("{connector} {child}/ ({})", files_label(*count)))
+                }
+                None => lines.push(format!("{connector} {child}")),
+            }
+        }
+        if hidden > 0 {
+            lines.push(format!("└── … {hidden} more"));
+        }
+    }
+    lines.extend(top_level_files);
+
+    lines.join("\n")
+}
+
+/// Applies the executable bits of the entry's `mode` if the unpacked file lost them
+/// (e.g. on filesystems where the permissions were not set while unpacking)
+#[cfg(unix)]
+fn ensure_executable(path: &Path, mode: u32) -> CliResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    const EXECUTABLE_BITS: u32 = 0o111;
+    if mode & EXECUTABLE_BITS == 0 {
+        return Ok(());
+    }
+    let mut permissions = fs::metadata(path)
+        .map_err(|e| write_error(path, e))?
+        .permissions();
+    if permissions.mode() & EXECUTABLE_BITS == mode & EXECUTABLE_BITS {
+        return Ok(());
+    }
+
+    debug!(
+        "Restoring executable mode {:o} of {}",
+        mode & 0o777,
+        path.display()
+    );
+    permissions.set_mode(permissions.mode() | (mode & EXECUTABLE_BITS));
+    fs::set_permissions(path, permissions).map_err(|e| write_error(path, e))
+}
+#[cfg(not(unix))]
+fn ensure_executable(_path: &Path, _mode: u32) -> CliResult<()> {
+    Ok(())
+}
+
+/// Whether the error is caused by the disk (or quota) being full
+fn is_storage_full(err: &io::Error) -> bool {
+    const ENOSPC: i32 = 28;
+    const EDQUOT: i32 = if cfg!(target_os = "linux") { 122 } else { 69 };
+    // tar wraps io errors with the entry path, keeping the original as the source
+    let source = err
+        .get_ref()
+        .and_then(|e| e.source())
+        .and_then(|e| e.downcast_ref::<io::Error>());
+
+    [Some(err), source].into_iter().flatten().any(|e| {
+        matches!(
+            e.kind(),
+            io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded
+        ) || (cfg!(unix) && matches!(e.raw_os_error(), Some(ENOSPC | EDQUOT)))
+    })
+}
+
+/// Error writing an entry into the output, calling out a full disk
+fn write_error(path: &Path, err: io::Error) -> CliError {
+    if is_storage_full(&err) {
+        CliError::io_custom(
+            format!("Ran out of disk space writing {}", path.display()),
+            err,
+        )
+    } else {
+        CliError::io_custom(
+            format!("Failed unpacking {} into output", path.display()),
+            err,
+        )
+    }
+}
+
+/// Names of the entries directly within the directory
+fn top_level_entries(dir: &Path) -> HashSet<OsString> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.file_name()).collect())
+        .unwrap_or_default()
+}
+
+/// Removes everything written into `dest` by a failed unpack, leaving entries
+/// that existed beforehand in place
+fn cleanup(dest: &Path, dest_existed: bool, existing: &HashSet<OsString>) {
+    if !dest_existed {
+        if let Err(e) = fs::remove_dir_all(dest) {
+            warn!(
+                "Failed cleaning up partially unpacked output {}",
+                dest.display()
+            );
+            debug!("{e:?}");
+        }
+        return;
+    }
+
+    for entry in fs::read_dir(dest).into_iter().flatten().flatten() {
+        if existing.contains(&entry.file_name()) {
+            continue;
+        }
+        let path = entry.path();
+        let removed = if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        match removed {
+            Ok(_) => debug!("Removed partially unpacked {}", path.display()),
+            Err(e) => {
+                warn!("Failed cleaning up partially unpacked {}", path.display());
+                debug!("{e:?}");
+            }
+        }
+    }
+}
+
+/// Where a stripped entry is written within `dest`, `None` (with a warning) if the
+/// entry would be written outside of it: an unsafe path, a parent directory that is
+/// a symlink leaving `dest`, or a link whose target leaves `dest`
+fn stripped_target<R: Read>(
+    entry: &mut tar::Entry<R>,
+    path: &Path,
+    dest: &Path,
+) -> CliResult<Option<PathBuf>> {
+    if !is_safe_relative(path) {
+        warn!("Skipping entry with unsafe path {}", path.display());
+        return Ok(None);
+    }
+
+    let entry_type = entry.header().entry_type();
+    if entry_type.is_hard_link() {
+        // hard link targets keep their unstripped archive path
+        warn!("Skipping hard link {}", path.display());
+        return Ok(None);
+    }
+    if entry_type.is_symlink() {
+        let link_target = entry
+            .link_name()
+            .map_err(|e| CliError::io_custom("Invalid symlink target in sdk archive", e))?;
+        if !link_target.is_some_and(|t| link_stays_within(path, &t)) {
+            warn!(
+                "Skipping symlink {}: target is not within the output",
+                path.display()
+            );
+            return Ok(None);
+        }
+    }
+
+    let target = dest.join(path);
+    if !parent_within(&target, dest) {
+        warn!(
+            "Skipping entry {}: it would be written through a symlink leaving the output",
+            path.display()
+        );
+        return Ok(None);
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| write_error(parent, e))?;
+    }
+    // an existing symlink at the target would be followed when writing a file
+    if target
+        .symlink_metadata()
+        .is_ok_and(|m| m.file_type().is_symlink())
+    {
+        fs::remove_file(&target).map_err(|e| write_error(path, e))?;
+    }
+
+    Ok(Some(target))
+}
+
+/// Whether the deepest existing ancestor of `target` resolves (following
+/// symlinks) to a directory within `dest`, which must be canonical
+fn parent_within(target: &Path, dest: &Path) -> bool {
+    let mut dir = target.parent();
+    while let Some(current) = dir {
+        if current.symlink_metadata().is_ok() {
+            // dangling symlinks fail to canonicalize and are rejected
+            return current
+                .canonicalize()
+                .is_ok_and(|resolved| resolved.starts_with(dest));
+        }
+        dir = current.parent();
+    }
+    false
+}
+
+/// Whether the symlink target, relative to the directory of `link`, lexically stays
+/// within the output. Absolute targets are never within the output
+fn link_stays_within(link: &Path, target: &Path) -> bool {
+    let mut depth = link
+        .parent()
+        .map(|p| p.components().count())
+        .unwrap_or_default();
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => depth -= 1,
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+/// Removes an unpacked symlink that resolves outside of `dest` through other
+/// symlinks, which the lexical target check cannot see
+fn remove_escaping_symlink(link_path: &Path, link: &Path, dest: &Path) -> io::Result<()> {
+    match link_path.canonicalize() {
+        Ok(resolved) if !resolved.starts_with(dest) => {
+            warn!(
+                "Removing symlink {}: target is not within the output",
+                link.display()
+            );
+            fs::remove_file(link_path)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether the path is relative and never steps outside of the directory it is joined to
+fn is_safe_relative(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+fn not_gzip_error(head: &[u8]) -> CliError {
+    if head.is_empty() {
+        return CliError::general("SDK archive is empty");
+    }
+
+    let text = String::from_utf8_lossy(head);
+    let mut preview: String = text.trim().chars().take(PREVIEW_LEN).collect();
+    if text.trim().chars().count() > PREVIEW_LEN {
+        preview.push_str("...");
+    }
+    CliError::general_debug(
+        format!("SDK archive is not gzip-compressed, received:\n{preview}"),
+        format!("First bytes: {:02x?}", &head[..head.len().min(16)]),
+    )
+}
+
+/// Reads the expected sha256 digest of the entry from its PAX extensions, if present
+fn pax_sha256<R: Read>(entry: &mut tar::Entry<'_, R>) -> CliResult<Option<String>> {
+    let Some(extensions) = entry
+        .pax_extensions()
+        .map_err(|e| CliError::io_custom("Failed reading sdk archive entry PAX headers", e))?
+    else {
+        return Ok(None);
+    };
+
+    for extension in extensions {
+        let extension = extension
+            .map_err(|e| CliError::io_custom("Failed reading sdk archive entry PAX headers", e))?;
+        if let (Ok(key), Ok(value)) = (extension.key(), extension.value()) {
+            if PAX_SHA256_KEYS.contains(&key) {
+                return Ok(Some(value.trim().to_lowercase()));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Verifies the unpacked file matches the size and sha256 digest recorded for its entry
+fn verify_entry(
+    unpacked: &Path,
+    entry_path: &Path,
+    expected_size: u64,
+    expected_sha256: &str,
+) -> CliResult<()> {
+    let content = fs::read(unpacked).map_err(|e| {
+        CliError::io_custom(format!("Failed verifying {}", entry_path.display()), e)
+    })?;
+
+    if content.len() as u64 != expected_size {
+        return Err(CliError::general(format!(
+            "SDK archive entry {} is corrupt: expected {expected_size} bytes, unpacked {}",
+            entry_path.display(),
+            content.len()
+        )));
+    }
+
+    let actual_sha256: String = Sha256::digest(&content)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    if actual_sha256 != expected_sha256 {
+        return Err(CliError::general_debug(
+            format!(
+                "SDK archive entry {} is corrupt: sha256 mismatch",
+                entry_path.display()
+            ),
+            format!("expected {expected_sha256}, got {actual_sha256}"),
+        ));
+    }
+
+    debug!("Verified {} ({expected_size} bytes)", entry_path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    /// Gzipped tar built by `append`
+    fn archive(append: impl FnOnce(&mut tar::Builder<GzEncoder<Vec<u8>>>)) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        append(&mut builder);
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn append_file(builder: &mut tar::Builder<GzEncoder<Vec<u8>>>, path: &str, mode: u32) {
+        let content = b"content";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(mode);
+        builder
+            .append_data(&mut header, path, &content[..])
+            .unwrap();
+    }
+
+    fn append_symlink(builder: &mut tar::Builder<GzEncoder<Vec<u8>>>, path: &str, target: &Path) {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, path, target).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn strip_components_does_not_write_through_escaping_symlinks() {
+        let root = tempfile::tempdir().unwrap();
+        let dest = root.path().join("out");
+        let outside = tempfile::tempdir().unwrap();
+        let bytes = archive(|b| {
+            // absolute target
+            append_symlink(b, "sdk/abs", outside.path());
+            append_file(b, "sdk/abs/evil", 0o644);
+            // lexically within the output, but resolves to its parent through `self`
+            append_symlink(b, "sdk/self", Path::new("."));
+            append_symlink(b, "sdk/up", Path::new("self/.."));
+            append_file(b, "sdk/up/evil", 0o644);
+        });
+
+        Unpacker::new(&Utf8PathBuf::from_path_buf(dest.clone()).unwrap())
+            .with_symlinks(SymlinkMode::Preserve)
+            .with_strip_components(1)
+            .unpack(&bytes[..])
+            .unwrap();
+
+        assert!(!outside.path().join("evil").exists());
+        assert!(!root.path().join("evil").exists());
+        for link in ["abs", "up"] {
+            let meta = dest.join(link).symlink_metadata().unwrap();
+            assert!(meta.is_dir(), "{link} was unpacked as a symlink");
+        }
+        assert!(dest.join("abs/evil").is_file());
+        assert!(dest.join("up/evil").is_file());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn executable_mode_round_trips() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dest = tempfile::tempdir().unwrap();
+        let bytes = archive(|b| {
+            append_file(b, "gradlew", 0o755);
+            append_file(b, "README.md", 0o644);
+            append_file(b, "setuid.sh", 0o4755);
+        });
+
+        Unpacker::new(&Utf8PathBuf::from_path_buf(dest.path().to_path_buf()).unwrap())
+            .unpack(&bytes[..])
+            .unwrap();
+
+        let mode = |path: &str| {
+            fs::metadata(dest.path().join(path))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o7777
+        };
+        assert_eq!(mode("gradlew") & 0o111, 0o111);
+        assert_eq!(mode("README.md") & 0o111, 0);
+        assert_eq!(mode("setuid.sh") & 0o7000, 0);
+        assert_eq!(mode("setuid.sh") & 0o111, 0o111);
+    }
+
+    #[test]
+    fn link_targets_must_stay_within_output() {
+        assert!(link_stays_within(Path::new("a/link"), Path::new("../b")));
+        assert!(link_stays_within(Path::new("a/link"), Path::new("./b/c")));
+        assert!(!link_stays_within(Path::new("link"), Path::new("../b")));
+        assert!(!link_stays_within(
+            Path::new("a/link"),
+            Path::new("/etc/passwd")
+        ));
+    }
+}
@@ -1,7 +1,8 @@
-use std::{env, fmt::Display, str::FromStr};
+use std::{env, fmt::Display, str::FromStr, sync::OnceLock};
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use log::{debug, warn};
+use toml_edit::DocumentMut;
 
 use crate::result::{CliError, CliResult};
 
@@ -9,8 +10,53 @@ pub enum ConfigKey {
     ConfigPath,
     ApiKey,
     ApiBaseUrl,
+    OutputFormat,
+    DiffTool,
+    NoEmoji,
+    ErrorLog,
+    PromptTimeout,
+    PromptTimeoutDefault,
+    GitUserName,
+    GitUserEmail,
+    RetryBaseDelay,
+    RetryMaxDelay,
+    RetryJitter,
+    Retries,
+    RetryIdempotentOnly,
+    ParseStrict,
+    MaxParallelRequests,
+    ColorTheme,
+    DefaultLang,
+    UseGitCli,
+    Profile,
 }
 impl ConfigKey {
+    /// Every config key, in the order `config show` lists them
+    pub const ALL: [ConfigKey; 22] = [
+        ConfigKey::ConfigPath,
+        ConfigKey::Profile,
+        ConfigKey::ApiKey,
+        ConfigKey::ApiBaseUrl,
+        ConfigKey::OutputFormat,
+        ConfigKey::ColorTheme,
+        ConfigKey::NoEmoji,
+        ConfigKey::ErrorLog,
+        ConfigKey::DiffTool,
+        ConfigKey::DefaultLang,
+        ConfigKey::PromptTimeout,
+        ConfigKey::PromptTimeoutDefault,
+        ConfigKey::GitUserName,
+        ConfigKey::GitUserEmail,
+        ConfigKey::UseGitCli,
+        ConfigKey::Retries,
+        ConfigKey::RetryBaseDelay,
+        ConfigKey::RetryMaxDelay,
+        ConfigKey::RetryJitter,
+        ConfigKey::RetryIdempotentOnly,
+        ConfigKey::MaxParallelRequests,
+        ConfigKey::ParseStrict,
+    ];
+
     /// Reads the content of the configured dotenv file
     /// and returns it's lines
     fn read_dotenv(&self) -> CliResult<Vec<String>> {
@@ -27,6 +73,31 @@ impl ConfigKey {
         Ok(lines)
     }
 
+    /// Key of the config value in a TOML config file, the env var name
+    /// without the `SIDEKO_` prefix in lowercase (e.g. `api_key`)
+    pub fn toml_key(&self) -> String {
+        let env_var = self.to_string();
+        env_var.strip_prefix("SIDEKO_").unwrap_or(&env_var).to_lowercase()
+    }
+
+    /// Name of the key in the dotenv config file, prefixed with the active
+    /// profile (e.g. `STAGING__SIDEKO_API_KEY`)
+    fn dotenv_key(&self) -> String {
+        match active_profile() {
+            Some(profile) => format!("{}{self}", profile_dotenv_prefix(&profile)),
+            None => self.to_string(),
+        }
+    }
+
+    /// Name of the keyring entry, namespaced by the active profile
+    /// (e.g. `sideko:staging:SIDEKO_API_KEY`)
+    fn keyring_name(&self) -> String {
+        match active_profile() {
+            Some(profile) => format!("sideko:{profile}:{self}"),
+            None => self.to_string(),
+        }
+    }
+
     /// Retrieves config key value from environment variable
     pub fn get_env(&self) -> Option<String> {
         env::var(self.to_string()).ok()
@@ -34,7 +105,7 @@ impl ConfigKey {
 
     /// Retrieves config key value from native key storage using keyring
     pub fn get_keyring(&self) -> Option<String> {
-        match  keyring::Entry::new("sideko", &self.to_string()) {
+        match  keyring::Entry::new("sideko", &self.keyring_name()) {
             Ok(entry) => {
                 match entry.get_password() {
                     Ok(v) => return Some(v),
@@ -56,46 +127,131 @@ impl ConfigKey {
     None
     }
 
+    /// Checks a value before it is persisted, only API keys are validated (and trimmed)
+    fn check_value(&self, val: String) -> CliResult<String> {
+        match self {
+            ConfigKey::ApiKey => validate_api_key(&val),
+            _ => Ok(val),
+        }
+    }
+
     /// Sets config key value in the native key storage using keyring
     pub fn set_keyring<S: ToString>(&self, val: S) -> CliResult<()> {
-        let entry = keyring::Entry::new("sideko", &self.to_string())?;
-        entry.set_password(&val.to_string())?;
+        let val = self.check_value(val.to_string())?;
+        let entry = keyring::Entry::new("sideko", &self.keyring_name())?;
+        entry.set_password(&val)?;
 
         debug!("Set keyring entry {self}");
 
         Ok(()) 
     }
 
-    /// Removes key from dotenv
-    pub fn unset_env(&self) -> CliResult<()> {
-        let curr_dotenv = self.read_dotenv()?;
-        let new_dotenv: Vec<String> = curr_dotenv.clone().into_iter().filter(|l| !l.starts_with(&format!("{self}="))).collect();
-
-        if new_dotenv.len() < curr_dotenv.len() {
-            debug!("Removed dotenv config {self}")
+    /// Retrieves config key value from the dotenv (or TOML) config file only
+    pub fn get_dotenv(&self) -> CliResult<Option<String>> {
+        let cfg_path = get_config_path()?;
+        if is_toml(&cfg_path) {
+            let doc = read_toml(&cfg_path)?;
+            return Ok(toml_profile_table(&doc).and_then(|t| t.get(&self.toml_key())).and_then(toml_value_string));
         }
 
+        let prefix = format!("{}=", self.dotenv_key());
+        Ok(self.read_dotenv()?.iter().find_map(|l| l.strip_prefix(&prefix)).map(|v| v.trim().trim_matches('"').to_string()))
+    }
+
+    /// Sets key in dotenv (or TOML) config file, replacing any existing value
+    pub fn set_env<S: ToString>(&self, val: S) -> CliResult<()> {
+        let val = self.check_value(val.to_string())?;
         let cfg_path = get_config_path()?;
+        if is_toml(&cfg_path) {
+            let key = self.toml_key();
+            let mut doc = read_toml(&cfg_path)?;
+            match active_profile() {
+                Some(profile) => {
+                    let profiles = doc[TOML_PROFILES_KEY].or_insert(toml_edit::table());
+                    if let Some(profiles) = profiles.as_table_mut() {
+                        // only the `[profiles.<name>]` headers are written
+                        profiles.set_implicit(true);
+                    }
+                    profiles[profile.as_str()][key.as_str()] = toml_edit::value(val.to_string());
+                }
+                None => doc[key.as_str()] = toml_edit::value(val.to_string()),
+            }
+            write_toml(&cfg_path, &doc)?;
+            debug!("Set TOML config {key}");
+            return Ok(());
+        }
+
+        let prefix = format!("{}=", self.dotenv_key());
+        let mut new_dotenv: Vec<String> = self.read_dotenv()?.into_iter().filter(|l| !l.starts_with(&prefix)).collect();
+        while new_dotenv.last().is_some_and(|l| l.trim().is_empty()) {
+            new_dotenv.pop();
+        }
+        new_dotenv.push(format!("{prefix}{}", val.to_string()));
+
+        create_config_dir(&cfg_path)?;
         std::fs::write(&cfg_path, new_dotenv.join("\n")).map_err(|e| {
             CliError::io_custom(format!("Failed updating sideko config {self}: {cfg_path}"), e)
         })?;
+        debug!("Set dotenv config {self}");
 
         Ok(())
     }
 
-    pub fn unset_keyring(&self) ->CliResult<()> {
-        let entry = keyring::Entry::new("sideko", &self.to_string())?;
+    /// Removes key from dotenv (or TOML) config file
+    ///
+    /// Returns: whether the key was in the config file
+    pub fn unset_env(&self) -> CliResult<bool> {
+        let cfg_path = get_config_path()?;
+        if !cfg_path.exists() {
+            return Ok(false);
+        }
+        if is_toml(&cfg_path) {
+            let mut doc = read_toml(&cfg_path)?;
+            let table = match active_profile() {
+                Some(profile) => doc.get_mut(TOML_PROFILES_KEY)
+                    .and_then(|p| p.get_mut(profile.as_str()))
+                    .and_then(|t| t.as_table_like_mut()),
+                None => Some(doc.as_table_mut() as &mut dyn toml_edit::TableLike),
+            };
+            if table.and_then(|t| t.remove(&self.toml_key())).is_none() {
+                return Ok(false);
+            }
+            write_toml(&cfg_path, &doc)?;
+            debug!("Removed TOML config {}", self.toml_key());
+            return Ok(true);
+        }
+
+        let curr_dotenv = self.read_dotenv()?;
+        let prefix = format!("{}=", self.dotenv_key());
+        let new_dotenv: Vec<String> = curr_dotenv.clone().into_iter().filter(|l| !l.starts_with(&prefix)).collect();
+
+        if new_dotenv.len() == curr_dotenv.len() {
+            return Ok(false);
+        }
+
+        std::fs::write(&cfg_path, new_dotenv.join("\n")).map_err(|e| {
+            CliError::io_custom(format!("Failed updating sideko config {self}: {cfg_path}"), e)
+        })?;
+        debug!("Removed dotenv config {self}");
+
+        Ok(true)
+    }
+
+    /// Removes the keyring entry
+    ///
+    /// Returns: whether the entry existed
+    pub fn unset_keyring(&self) ->CliResult<bool> {
+        let entry = keyring::Entry::new("sideko", &self.keyring_name())?;
         match entry.delete_credential() {
-            Ok(_) => debug!("Removed keyring entry {self}"),
-            Err(e) => {
-                if !matches!(e, keyring::Error::NoEntry) {
-                    // genuine error has occurred
-                    return Err(e.into())
-                }
+            Ok(_) => {
+                debug!("Removed keyring entry {self}");
+                Ok(true)
             },
+            // no entry is a valid error here
+            Err(keyring::Error::NoEntry) => Ok(false),
+            // genuine error has occurred
+            Err(e) => Err(e.into()),
         }
-
-        Ok(())
     }
 
 
@@ -106,17 +262,211 @@ impl Display for ConfigKey {
             ConfigKey::ApiKey => "SIDEKO_API_KEY",
             ConfigKey::ApiBaseUrl => "SIDEKO_BASE_URL",
             ConfigKey::ConfigPath => "SIDEKO_CONFIG_PATH",
+            ConfigKey::OutputFormat => "SIDEKO_OUTPUT_FORMAT",
+            ConfigKey::DiffTool => "SIDEKO_DIFF_TOOL",
+            ConfigKey::NoEmoji => "SIDEKO_NO_EMOJI",
+            ConfigKey::ErrorLog => "SIDEKO_ERROR_LOG",
+            ConfigKey::PromptTimeout => "SIDEKO_PROMPT_TIMEOUT",
+            ConfigKey::PromptTimeoutDefault => "SIDEKO_PROMPT_TIMEOUT_DEFAULT",
+            ConfigKey::GitUserName => "SIDEKO_GIT_USER_NAME",
+            ConfigKey::GitUserEmail => "SIDEKO_GIT_USER_EMAIL",
+            ConfigKey::RetryBaseDelay => "SIDEKO_RETRY_BASE_DELAY",
+            ConfigKey::RetryMaxDelay => "SIDEKO_RETRY_MAX_DELAY",
+            ConfigKey::RetryJitter => "SIDEKO_RETRY_JITTER",
+            ConfigKey::Retries => "SIDEKO_RETRIES",
+            ConfigKey::RetryIdempotentOnly => "SIDEKO_RETRY_IDEMPOTENT_ONLY",
+            ConfigKey::ParseStrict => "SIDEKO_PARSE_STRICT",
+            ConfigKey::MaxParallelRequests => "SIDEKO_MAX_PARALLEL_REQUESTS",
+            ConfigKey::ColorTheme => "SIDEKO_COLOR_THEME",
+            ConfigKey::DefaultLang => "SIDEKO_DEFAULT_LANG",
+            ConfigKey::UseGitCli => "SIDEKO_USE_GIT_CLI",
+            ConfigKey::Profile => "SIDEKO_PROFILE",
         };
 
         write!(f, "{env_var}")
     }
 }
 
+/// TOML config table holding a `[profiles.<name>]` table per profile
+const TOML_PROFILES_KEY: &str = "profiles";
+
+/// Profile selected with `--profile` (or its env var), `None` for the default profile
+pub(crate) fn active_profile() -> Option<String> {
+    ConfigKey::Profile.get_env().map(|p| p.trim().to_string()).filter(|p| !p.is_empty())
+}
+
+/// Prefix of the profile's keys in the dotenv config file (e.g. `STAGING__`)
+fn profile_dotenv_prefix(profile: &str) -> String {
+    format!("{}__", profile.to_uppercase())
+}
+
+/// Config table of the active profile in the TOML config, the top-level table for the default profile
+fn toml_profile_table(doc: &DocumentMut) -> Option<&dyn toml_edit::TableLike> {
+    match active_profile() {
+        Some(profile) => doc.get(TOML_PROFILES_KEY)?.get(&profile)?.as_table_like(),
+        None => Some(doc.as_table() as &dyn toml_edit::TableLike),
+    }
+}
+
+/// Whether the config file is TOML (by its `.toml` extension) rather than dotenv
+pub(crate) fn is_toml(cfg_path: &Utf8Path) -> bool {
+    cfg_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+/// Parses the TOML config file, empty if it does not exist yet
+fn read_toml(cfg_path: &Utf8Path) -> CliResult<DocumentMut> {
+    if !cfg_path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    let content = std::fs::read_to_string(cfg_path).map_err(|e| {
+        CliError::io_custom(format!("Failed loading sideko config file: {cfg_path}"), e)
+    })?;
+    content.parse::<DocumentMut>().map_err(|e| {
+        CliError::general_debug(format!("Failed parsing sideko TOML config: {cfg_path}"), format!("{e}"))
+    })
+}
+
+/// Writes the TOML config file, comments and the formatting of untouched keys are preserved
+fn write_toml(cfg_path: &Utf8Path, doc: &DocumentMut) -> CliResult<()> {
+    create_config_dir(cfg_path)?;
+    std::fs::write(cfg_path, doc.to_string()).map_err(|e| {
+        CliError::io_custom(format!("Failed updating sideko config: {cfg_path}"), e)
+    })
+}
+
+/// Creates the directory of the config file, which does not exist yet for a
+/// first write to the XDG config location
+pub(crate) fn create_config_dir(cfg_path: &Utf8Path) -> CliResult<()> {
+    match cfg_path.parent() {
+        Some(dir) if !dir.as_str().is_empty() && !dir.exists() => {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                CliError::io_custom(format!("Failed creating sideko config directory: {dir}"), e)
+            })?;
+            debug!("Created config directory {dir}");
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// String form of a top-level TOML config value, `None` for tables and arrays
+fn toml_value_string(item: &toml_edit::Item) -> Option<String> {
+    match item.as_value()? {
+        toml_edit::Value::String(s) => Some(s.value().clone()),
+        toml_edit::Value::Integer(i) => Some(i.value().to_string()),
+        toml_edit::Value::Float(f) => Some(f.value().to_string()),
+        toml_edit::Value::Boolean(b) => Some(b.value().to_string()),
+        toml_edit::Value::Datetime(d) => Some(d.value().to_string()),
+        toml_edit::Value::Array(_) | toml_edit::Value::InlineTable(_) => None,
+    }
+}
+
+/// Sets the env vars of the TOML config values (of the active profile), without
+/// overriding variables already set in the environment (like `dotenvy`)
+fn load_toml(cfg_path: &Utf8Path) -> CliResult<()> {
+    let doc = read_toml(cfg_path)?;
+    let Some(table) = toml_profile_table(&doc) else {
+        return Ok(());
+    };
+    for (key, item) in table.iter() {
+        if active_profile().is_none() && key == TOML_PROFILES_KEY {
+            continue;
+        }
+        let Some(val) = toml_value_string(item) else {
+            warn!("Ignoring `{key}` in sideko config {cfg_path}, expected a string, number, or boolean");
+            continue;
+        };
+        let env_var = format!("SIDEKO_{}", key.to_uppercase());
+        if env::var_os(&env_var).is_none() {
+            env::set_var(env_var, val);
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn load() -> CliResult<()> {
     let cfg_path = get_config_path()?;
-    if cfg_path.exists() {
+    let profile = active_profile();
+    if let Some(profile) = &profile {
+        super::validators::validate_profile_name(profile).map_err(CliError::general)?;
+        debug!("Using config profile {profile}");
+    }
+
+    if !cfg_path.exists() {
+        return Ok(());
+    }
+    if is_toml(&cfg_path) {
+        load_toml(&cfg_path)?;
+    } else if let Some(profile) = &profile {
+        load_dotenv_profile(&cfg_path, profile)?;
+    } else {
         dotenvy::from_path(&cfg_path).map_err(|e| CliError::general_debug(format!("Failed loading sideko config: {cfg_path}"), e))?;
-        debug!("Loaded config: {cfg_path}");
+    }
+    debug!("Loaded config: {cfg_path}");
+    Ok(())
+}
+
+/// Entries of the config file that are not a recognized config key, described with
+/// their line (dotenv) or key path (TOML). Comments and blank lines are ignored
+pub(crate) fn unrecognized_entries(cfg_path: &Utf8Path) -> CliResult<Vec<String>> {
+    let is_known = |key: &str| {
+        ConfigKey::ALL.iter().any(|k| {
+            let env_var = k.to_string();
+            // dotenv keys may be prefixed with a profile (e.g. `STAGING__SIDEKO_API_KEY`)
+            key == env_var || key.strip_suffix(&env_var).is_some_and(|p| p.len() > 2 && p.ends_with("__"))
+        })
+    };
+
+    if is_toml(cfg_path) {
+        let doc = read_toml(cfg_path)?;
+        let toml_keys: Vec<String> = ConfigKey::ALL.iter().map(|k| k.toml_key()).collect();
+        let mut unrecognized = vec![];
+        for (key, item) in doc.iter() {
+            if key == TOML_PROFILES_KEY {
+                for (profile, table) in item.as_table_like().into_iter().flat_map(|t| t.iter()) {
+                    for (key, _) in table.as_table_like().into_iter().flat_map(|t| t.iter()) {
+                        if !toml_keys.iter().any(|k| k == key) {
+                            unrecognized.push(format!("`{TOML_PROFILES_KEY}.{profile}.{key}`"));
+                        }
+                    }
+                }
+            } else if !toml_keys.iter().any(|k| k == key) {
+                unrecognized.push(format!("`{key}`"));
+            }
+        }
+        return Ok(unrecognized);
+    }
+
+    let content = std::fs::read_to_string(cfg_path).map_err(|e| {
+        CliError::io_custom(format!("Failed loading sideko config file: {cfg_path}"), e)
+    })?;
+    let unrecognized = content.lines().enumerate().filter_map(|(idx, line)| {
+        let entry = line.trim();
+        if entry.is_empty() || entry.starts_with('#') {
+            return None;
+        }
+        let entry = entry.strip_prefix("export ").unwrap_or(entry);
+        match entry.split_once('=') {
+            Some((key, _)) if is_known(key.trim()) => None,
+            _ => Some(format!("line {}: `{line}`", idx + 1)),
+        }
+    }).collect();
+
+    Ok(unrecognized)
+}
+
+/// Sets the env vars of the profile's keys in the dotenv config, without overriding
+/// variables already set in the environment. Keys of the default profile are not loaded
+fn load_dotenv_profile(cfg_path: &Utf8Path, profile: &str) -> CliResult<()> {
+    let prefix = profile_dotenv_prefix(profile);
+    let entries = dotenvy::from_path_iter(cfg_path).map_err(|e| CliError::general_debug(format!("Failed loading sideko config: {cfg_path}"), e))?;
+    for entry in entries {
+        let (key, val) = entry.map_err(|e| CliError::general_debug(format!("Failed loading sideko config: {cfg_path}"), e))?;
+        if let Some(key) = key.strip_prefix(&prefix) {
+            if env::var_os(key).is_none() {
+                env::set_var(key, val);
+            }
+        }
     }
     Ok(())
 }
@@ -124,21 +474,78 @@ pub(crate) fn load() -> CliResult<()> {
 /// First tries retrieving the sideko API key from the ConfigKey::ApiKey env var,
 /// if that is not set then it will try to retrieve it from keyring
 pub(crate) fn get_api_key() -> Option<String> {
+    get_api_key_with_source().map(|(key, _)| key)
+}
+
+/// Same as `get_api_key`, also returning where the key was found (`env` or `keyring`)
+pub(crate) fn get_api_key_with_source() -> Option<(String, &'static str)> {
     if let Some(env_key) = ConfigKey::ApiKey.get_env() {
         debug!("Retrieved API key from env");
-        Some(env_key)
+        Some((env_key, "env"))
     }
     else if let Some(keyring_key) = ConfigKey::ApiKey.get_keyring() {
         debug!("Retrieved API key from keyring");
-        Some(keyring_key)
+        Some((keyring_key, "keyring"))
     } else {
         None
     }
 }
 
+/// Describes the API key env var shadowing a different key stored in the keyring.
+/// Only called by the config inspection commands, reading the keyring can prompt
+/// for the keychain password or hang without a desktop session (e.g. in CI)
+pub(crate) fn api_key_keyring_conflict() -> Option<String> {
+    let env_key = ConfigKey::ApiKey.get_env()?;
+    let keyring_key = ConfigKey::ApiKey.get_keyring().filter(|k| *k != env_key)?;
+    Some(format!(
+        "{} ({}) differs from the API key stored in the keyring ({}), the env var is taking precedence",
+        ConfigKey::ApiKey, mask_api_key(&env_key), mask_api_key(&keyring_key)
+    ))
+}
+
+
+/// Where a config value was resolved from
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueSource {
+    Flag,
+    Env,
+    Default,
+}
+impl Display for ValueSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self {
+            ValueSource::Flag => "flag",
+            ValueSource::Env => "env",
+            ValueSource::Default => "default",
+        };
+
+        write!(f, "{source}")
+    }
+}
+
+/// Config path passed with the global `--config` flag
+static CONFIG_PATH_FLAG: OnceLock<Utf8PathBuf> = OnceLock::new();
+
+/// Sets the config path from the `--config` flag, taking priority over the environment
+pub(crate) fn set_config_path_flag(path: &Utf8PathBuf) {
+    let _ = CONFIG_PATH_FLAG.set(path.clone());
+    env::set_var(ConfigKey::ConfigPath.to_string(), path);
+}
+
+/// Where `get_config_path` resolves the config path from
+pub(crate) fn get_config_path_source() -> ValueSource {
+    if CONFIG_PATH_FLAG.get().is_some() {
+        ValueSource::Flag
+    } else if ConfigKey::ConfigPath.get_env().is_some() {
+        ValueSource::Env
+    } else {
+        ValueSource::Default
+    }
+}
 
 /// Retrieves the config path from user-set ConfigKey::ConfigPath,
-/// defaulting to $HOME/.sideko if not set
+/// defaulting to `get_default_config_path` if not set
 pub(crate) fn get_config_path() -> CliResult<Utf8PathBuf> {
     if let Some(p) = ConfigKey::ConfigPath.get_env() {
         let path = Utf8PathBuf::from_str(&p).map_err(|e| {
@@ -156,32 +563,187 @@ pub(crate) fn get_config_path() -> CliResult<Utf8PathBuf> {
     }
 
 }
+/// Default config path: `$XDG_CONFIG_HOME/sideko/config` (or `~/.config/sideko/config`)
+/// on Linux, `$HOME/.sideko` elsewhere or when it already exists
 pub(crate) fn get_default_config_path() -> CliResult<Utf8PathBuf> {
-    let home = env::var("HOME")
-            .map_err(|_| CliError::general("Unable to build default config path: $HOME is not set"))?;
-        let mut default_path = Utf8PathBuf::from_str(&home).map_err(|e| {
-            CliError::general_debug(
-                format!(
-                "Unable to build default config path: $HOME is set to an ill-formatted path: {home}"
-    
-            ),
-                format!("{e:?}"),
-            )
+    let legacy_path = home_dir()?.join(".sideko");
+    if !cfg!(target_os = "linux") || legacy_path.exists() {
+        return Ok(legacy_path);
+    }
+
+    // relative `$XDG_CONFIG_HOME` values are invalid per the XDG base directory spec
+    let config_home = env::var("XDG_CONFIG_HOME")
+        .ok()
+        .map(Utf8PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .map_or_else(|| home_dir().map(|home| home.join(".config")), Ok)?;
+    Ok(config_home.join("sideko").join("config"))
+}
+
+/// Home directory from `$HOME`, falling back to `%USERPROFILE%` (set on Windows
+/// where `$HOME` usually is not) and then the platform's home directory lookup
+fn home_dir() -> CliResult<Utf8PathBuf> {
+    let from_env = ["HOME", "USERPROFILE"]
+        .into_iter()
+        .find_map(|var| env::var(var).ok().filter(|v| !v.is_empty()).map(|v| (var, v)));
+    let Some((var, home)) = from_env else {
+        let home = dirs::home_dir().ok_or_else(|| {
+            CliError::general("Unable to build default config path: neither $HOME nor %USERPROFILE% is set")
         })?;
-        default_path.push(".sideko");
-    
-        Ok(default_path)
+        return Utf8PathBuf::from_path_buf(home).map_err(|home| {
+            CliError::general(format!("Unable to build default config path: home directory is not valid UTF-8: {}", home.display()))
+        });
+    };
+
+    Utf8PathBuf::from_str(&home).map_err(|e| {
+        CliError::general_debug(
+            format!("Unable to build default config path: {var} is set to an ill-formatted path: {home}"),
+            format!("{e:?}"),
+        )
+    })
+}
+
+/// Where `get_base_url` resolves the base url from
+pub(crate) fn get_base_url_source() -> ValueSource {
+    if ConfigKey::ApiBaseUrl.get_env().is_some() {
+        ValueSource::Env
+    } else {
+        ValueSource::Default
+    }
+}
+
+/// Shortest value accepted as a Sideko API key
+const MIN_API_KEY_LEN: usize = 20;
+
+/// Trims surrounding whitespace (e.g. the newline of a copy-paste) from an API key and
+/// rejects values that clearly are not one, to avoid the 401s a bad paste leads to
+pub(crate) fn validate_api_key(key: &str) -> CliResult<String> {
+    let key = key.trim();
+    let problem = if key.is_empty() {
+        Some("it is empty".to_string())
+    } else if url::Url::parse(key).is_ok_and(|u| u.has_host()) {
+        Some("it looks like a URL".to_string())
+    } else if key.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        Some("it contains whitespace".to_string())
+    } else if key.len() < MIN_API_KEY_LEN {
+        Some(format!("it is shorter than {MIN_API_KEY_LEN} characters"))
+    } else {
+        None
+    };
+
+    match problem {
+        Some(problem) => Err(CliError::general(format!(
+            "The provided value does not look like a Sideko API key, {problem}. Copy the key again from the Sideko dashboard, or run `sideko login` without `--key` to log in via the browser"
+        ))),
+        None => Ok(key.to_string()),
+    }
+}
+
+/// Masks all but the last 4 characters of the API key for display
+pub(crate) fn mask_api_key(key: &str) -> String {
+    let visible: String = key.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+    if key.chars().count() <= 8 {
+        "****".into()
+    } else {
+        format!("****{visible}")
+    }
+}
+
+/// Native credential store used by keyring on this platform
+pub(crate) fn keyring_backend() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macOS Keychain"
+    } else if cfg!(target_os = "windows") {
+        "Windows Credential Manager"
+    } else if cfg!(target_os = "linux") {
+        "Linux kernel keyutils"
+    } else {
+        "unsupported"
+    }
 }
 
 /// Retrieves Sideko API base url from user-set ConfigKey::ApiBaseUrl,
-/// defaulting to production environment if not set
-pub(crate) fn get_base_url() -> String {
-    let url = ConfigKey::ApiBaseUrl.get_env()
-        .unwrap_or(sideko_rest_api::environment::Environment::default().to_string());
+/// defaulting to production environment if not set. Errors on unparseable or
+/// non-HTTP(S) urls and appends `/v1` when the url has no path
+pub(crate) fn get_base_url() -> CliResult<String> {
+    let Some(raw) = ConfigKey::ApiBaseUrl.get_env() else {
+        return Ok(sideko_rest_api::environment::Environment::default().to_string());
+    };
 
+    let mut url = super::validators::validate_url(&raw)
+        .map_err(|e| CliError::general(format!("{e} (set in {})", ConfigKey::ApiBaseUrl)))?;
+    if url.path() == "/" {
+        url.set_path("/v1");
+        debug!("Sideko API base url has no path, using {url}");
+    }
+
+    let url = url.as_str().trim_end_matches('/').to_string();
     if !url.ends_with("/v1") {
         warn!("Sideko API base url does not end with `/v1`, this probably means it is wrong")
     }
 
-    url
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Serializes the tests mutating the process environment
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Runs `f` with the env `vars` set (or removed when `None`), restoring them afterwards
+    fn with_env<T>(vars: &[(&str, Option<&str>)], f: impl FnOnce() -> T) -> T {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let saved: Vec<(&str, Option<String>)> = vars.iter().map(|(var, _)| (*var, env::var(var).ok())).collect();
+        for (var, val) in vars {
+            match val {
+                Some(val) => env::set_var(var, val),
+                None => env::remove_var(var),
+            }
+        }
+        let res = f();
+        for (var, val) in saved {
+            match val {
+                Some(val) => env::set_var(var, val),
+                None => env::remove_var(var),
+            }
+        }
+        res
+    }
+
+    #[test]
+    fn home_dir_prefers_home_over_userprofile() {
+        let home = with_env(&[("HOME", Some("/home/sideko")), ("USERPROFILE", Some("C:\\Users\\sideko"))], home_dir);
+        assert_eq!(home.unwrap(), Utf8PathBuf::from("/home/sideko"));
+    }
+
+    #[test]
+    fn home_dir_falls_back_to_userprofile() {
+        for unset_home in [None, Some("")] {
+            let home = with_env(&[("HOME", unset_home), ("USERPROFILE", Some("C:\\Users\\sideko"))], home_dir);
+            assert_eq!(home.unwrap(), Utf8PathBuf::from("C:\\Users\\sideko"));
+        }
+    }
+
+    #[test]
+    fn default_config_path_is_under_home() {
+        let home = tempfile::tempdir().unwrap();
+        let home_path = Utf8PathBuf::from_path_buf(home.path().to_path_buf()).unwrap();
+        let vars = [("HOME", Some(home_path.as_str())), ("USERPROFILE", None), ("XDG_CONFIG_HOME", None)];
+
+        let path = with_env(&vars, get_default_config_path).unwrap();
+        if cfg!(target_os = "linux") {
+            assert_eq!(path, home_path.join(".config").join("sideko").join("config"));
+        } else {
+            assert_eq!(path, home_path.join(".sideko"));
+        }
+
+        // an existing legacy config keeps being used
+        std::fs::write(home_path.join(".sideko"), "").unwrap();
+        let path = with_env(&vars, get_default_config_path).unwrap();
+        assert_eq!(path, home_path.join(".sideko"));
+    }
 }
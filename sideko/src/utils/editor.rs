@@ -1,7 +1,10 @@
-use crate::result::{CliError, CliResult};
+use crate::{
+    result::{CliError, CliResult},
+    utils::prompt,
+};
 use camino::Utf8PathBuf;
 use inquire::Confirm;
-use log::{debug, info};
+use log::{debug, info, warn};
 use std::env;
 use std::process::Command;
 
@@ -20,6 +23,10 @@ pub fn get_editor() -> String {
 }
 
 pub fn open_config_in_editor(config_path: &Utf8PathBuf) -> CliResult<()> {
+    if !prompt::terminal_available() {
+        warn!("An earlier prompt timed out and still holds the terminal, skipping the SDK config review: {config_path}");
+        return Ok(());
+    }
     let editor = get_editor();
     debug!("Using editor: {}", editor);
     info!("Opening editor for file: {} - please review the SDK config and save any changes before closing", config_path);
@@ -34,10 +41,15 @@ pub fn open_config_in_editor(config_path: &Utf8PathBuf) -> CliResult<()> {
             )
         })?;
 
-    let confirmed = Confirm::new("Have you completed reviewing the SDK config?")
-        .with_default(true)
-        .with_help_message("'n' to open the SDK config again")
-        .prompt()?;
+    let confirmed = prompt::prompt(
+        || {
+            Confirm::new("Have you completed reviewing the SDK config?")
+                .with_default(true)
+                .with_help_message("'n' to open the SDK config again")
+                .prompt()
+        },
+        Some(true),
+    )?;
 
     if !confirmed {
         return open_config_in_editor(config_path);
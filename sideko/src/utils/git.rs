@@ -0,0 +1,327 @@
+use std::{collections::HashSet, fs, process, str, sync::OnceLock};
+
+use camino::Utf8Path;
+use log::debug;
+
+use crate::{
+    result::{CliError, CliResult},
+    utils::config::ConfigKey,
+};
+
+/// Whether `--use-git-cli` (or its env var) is set, running the `git` CLI for the
+/// status checks and patch applies otherwise done with libgit2
+pub fn use_cli() -> bool {
+    ConfigKey::UseGitCli
+        .get_env()
+        .is_some_and(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false"))
+}
+
+/// Whether the `git` CLI is installed, checked once
+pub fn cli_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        process::Command::new("git")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+fn open(repo: &Utf8Path) -> CliResult<git2::Repository> {
+    git2::Repository::open(repo).map_err(|e| {
+        CliError::general_debug(
+            format!("Failed opening git repository {repo}: {}", e.message()),
+            format!("{e:?}"),
+        )
+    })
+}
+
+/// Errors when the `git` CLI is not installed, for features libgit2 does not cover
+pub fn require_cli(feature: &str) -> CliResult<()> {
+    if cli_available() {
+        Ok(())
+    } else {
+        Err(CliError::general(format!(
+            "{feature} requires the `git` CLI, install git or run without it"
+        )))
+    }
+}
+
+/// Runs `git` with `args` in the `repo` directory, returning the output
+/// regardless of the exit status
+pub fn run(repo: &Utf8Path, args: &[&str]) -> CliResult<process::Output> {
+    require_cli(&format!("`git {}`", args.join(" ")))?;
+    debug!("Running `git {}` in {repo}", args.join(" "));
+    process::Command::new("git")
+        .current_dir(repo)
+        .args(args)
+        .output()
+        .map_err(|e| {
+            CliError::general_debug(
+                format!(
+                    "Failed to run `git {}`, is `git` installed?",
+                    args.join(" ")
+                ),
+                format!("{e:?}"),
+            )
+        })
+}
+
+/// Applies the patch file to the files in `dir` with `git apply`, returning the
+/// output regardless of the exit status
+///
+/// Repository discovery stops at `dir`, so the patch paths are relative to `dir`
+/// even when it is nested in another git repository.
+pub fn apply_in_dir(dir: &Utf8Path, patch: &Utf8Path) -> CliResult<process::Output> {
+    require_cli("`git apply`")?;
+    debug!("Running `git apply {patch}` in {dir}");
+    let ceiling = dir.parent().unwrap_or(dir);
+    process::Command::new("git")
+        .current_dir(dir)
+        .env("GIT_CEILING_DIRECTORIES", ceiling)
+        .args(["apply", patch.as_str()])
+        .output()
+        .map_err(|e| {
+            CliError::general_debug(
+                "Failed to run `git apply`, is `git` installed?",
+                format!("{e:?}"),
+            )
+        })
+}
+
+/// Maps a libgit2 error of an operation on the repo to a CLI error
+fn git2_error(what: &str, repo: &Utf8Path, e: git2::Error) -> CliError {
+    CliError::general_debug(
+        format!("Failed {what} in {repo}: {}", e.message()),
+        format!("{e:?}"),
+    )
+}
+
+/// Lists paths with uncommitted changes in the repo, including untracked files
+/// but not ignored ones (like `git status --porcelain`)
+pub fn changed_paths(repo: &Utf8Path) -> CliResult<Vec<String>> {
+    status_paths(repo, false)
+}
+
+/// Same as `changed_paths`, listing each file in untracked directories instead
+/// of the directory (like `git status --porcelain --untracked-files=all`)
+pub fn changed_files(repo: &Utf8Path) -> CliResult<Vec<String>> {
+    status_paths(repo, true)
+}
+
+fn status_paths(repo: &Utf8Path, untracked_files: bool) -> CliResult<Vec<String>> {
+    if use_cli() {
+        let mut args = vec!["status", "--porcelain"];
+        if untracked_files {
+            args.push("--untracked-files=all");
+        }
+        let output = run(repo, &args)?;
+        if !output.status.success() {
+            return Err(CliError::general_debug(
+                format!("Failed checking the status of {repo}"),
+                describe_output(&format!("git {}", args.join(" ")), &output),
+            ));
+        }
+        return Ok(str::from_utf8(&output.stdout)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|l| l.get(3..))
+            // renames are reported as `<from> -> <to>`
+            .map(|p| p.rsplit(" -> ").next().unwrap_or(p).trim_matches('"'))
+            .map(String::from)
+            .collect());
+    }
+
+    let repository = open(repo)?;
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .include_ignored(false)
+        .recurse_untracked_dirs(untracked_files);
+    let statuses = repository.statuses(Some(&mut options)).map_err(|e| {
+        CliError::general_debug(
+            format!("Failed checking the status of {repo}: {}", e.message()),
+            format!("{e:?}"),
+        )
+    })?;
+    Ok(statuses
+        .iter()
+        .filter_map(|entry| entry.path().map(String::from))
+        .collect())
+}
+
+/// Outcome of applying a patch with `git apply` or libgit2
+pub struct ApplyOutput {
+    pub success: bool,
+    /// `git apply` error output, or the libgit2 error message
+    pub stderr: Vec<u8>,
+    /// Description of the apply for debug logging
+    pub details: String,
+}
+impl ApplyOutput {
+    pub fn from_cli(command: &str, output: &process::Output) -> Self {
+        Self {
+            success: output.status.success(),
+            stderr: output.stderr.clone(),
+            details: describe_output(command, output),
+        }
+    }
+}
+
+/// Applies the patch file (relative to `repo`) to the working tree with libgit2, or
+/// with `git apply` when `--use-git-cli` is set. Nothing is changed if any hunk fails
+pub fn apply(repo: &Utf8Path, patch: &str) -> CliResult<ApplyOutput> {
+    if use_cli() {
+        let output = run(repo, &["apply", patch])?;
+        return Ok(ApplyOutput::from_cli("git apply", &output));
+    }
+
+    debug!("Applying {patch} to {repo} with libgit2");
+    let repository = open(repo)?;
+    let patch_path = repo.join(patch);
+    let content = fs::read(&patch_path)
+        .map_err(|e| CliError::io_custom(format!("Failed reading patch {patch_path}"), e))?;
+    let res = git2::Diff::from_buffer(&content)
+        .and_then(|diff| repository.apply(&diff, git2::ApplyLocation::WorkDir, None));
+    Ok(match res {
+        Ok(()) => ApplyOutput {
+            success: true,
+            stderr: vec![],
+            details: format!("libgit2 apply {patch}: applied"),
+        },
+        Err(e) => ApplyOutput {
+            success: false,
+            stderr: e.message().as_bytes().to_vec(),
+            details: format!("libgit2 apply {patch}: {e:?}"),
+        },
+    })
+}
+
+/// Default identity for commits made by the CLI
+pub const DEFAULT_USER_NAME: &str = "sideko-bot";
+pub const DEFAULT_USER_EMAIL: &str = "bot@sideko.dev";
+
+/// Stages all changes and commits them with the given identity, passed with `-c`
+/// so the user's git config is never modified
+pub fn commit_all(
+    repo: &Utf8Path,
+    message: &str,
+    user_name: &str,
+    user_email: &str,
+) -> CliResult<()> {
+    let add_output = run(repo, &["add", "--all"])?;
+    if !add_output.status.success() {
+        return Err(CliError::general_debug(
+            "Failed to stage changes for commit",
+            describe_output("git add --all", &add_output),
+        ));
+    }
+
+    let user_name = format!("user.name={user_name}");
+    let user_email = format!("user.email={user_email}");
+    let commit_output = run(
+        repo,
+        &[
+            "-c",
+            &user_name,
+            "-c",
+            &user_email,
+            "commit",
+            "--message",
+            message,
+        ],
+    )?;
+    if !commit_output.status.success() {
+        return Err(CliError::general_debug(
+            "Failed to commit changes",
+            describe_output("git commit", &commit_output),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Formats a git command output for debug logging
+pub fn describe_output(command: &str, output: &process::Output) -> String {
+    format!(
+        "`{command}` (exit status {exit})\nstdout:\n{stdout}\nstderr:\n{stderr}",
+        exit = output.status,
+        stdout = str::from_utf8(&output.stdout).unwrap_or_default(),
+        stderr = str::from_utf8(&output.stderr).unwrap_or_default(),
+    )
+}
+
+/// Whether the index has changes staged for commit
+pub fn has_staged_changes(repo: &Utf8Path) -> CliResult<bool> {
+    if use_cli() {
+        let output = run(repo, &["diff", "--cached", "--quiet"])?;
+        return Ok(!output.status.success());
+    }
+
+    let repository = open(repo)?;
+    // without commits everything in the index is staged
+    let head_tree = repository.head().ok().and_then(|h| h.peel_to_tree().ok());
+    let diff = repository
+        .diff_tree_to_index(head_tree.as_ref(), None, None)
+        .map_err(|e| git2_error("checking staged changes", repo, e))?;
+    Ok(diff.deltas().len() > 0)
+}
+
+/// Lists paths with unresolved merge conflicts in the repo
+pub fn conflicted_paths(repo: &Utf8Path) -> CliResult<Vec<String>> {
+    if use_cli() {
+        let output = run(repo, &["diff", "--name-only", "--diff-filter=U"])?;
+        return Ok(str::from_utf8(&output.stdout)
+            .unwrap_or_default()
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(String::from)
+            .collect());
+    }
+
+    let repository = open(repo)?;
+    let index = repository
+        .index()
+        .map_err(|e| git2_error("reading the index", repo, e))?;
+    let conflicts = index
+        .conflicts()
+        .map_err(|e| git2_error("listing merge conflicts", repo, e))?;
+    let mut paths = vec![];
+    for conflict in conflicts {
+        let conflict = conflict.map_err(|e| git2_error("listing merge conflicts", repo, e))?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            paths.push(String::from_utf8_lossy(&entry.path).into_owned());
+        }
+    }
+    Ok(paths)
+}
+
+/// Filters `paths` (relative to `repo`) down to the ones ignored by git (like `git check-ignore`)
+pub fn ignored_paths<'a>(repo: &Utf8Path, paths: &[&'a str]) -> CliResult<Vec<&'a str>> {
+    if use_cli() {
+        let mut args = vec!["check-ignore", "--"];
+        args.extend(paths);
+        let output = run(repo, &args)?;
+        let ignored: HashSet<&str> = str::from_utf8(&output.stdout)
+            .unwrap_or_default()
+            .lines()
+            .collect();
+        return Ok(paths
+            .iter()
+            .copied()
+            .filter(|p| ignored.contains(p))
+            .collect());
+    }
+
+    let repository = open(repo)?;
+    let mut ignored = vec![];
+    for path in paths {
+        if repository
+            .is_path_ignored(path)
+            .map_err(|e| git2_error("checking ignored files", repo, e))?
+        {
+            ignored.push(*path);
+        }
+    }
+    Ok(ignored)
+}
@@ -0,0 +1,61 @@
+use std::{
+    fs,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use log::{debug, warn};
+
+/// Partially written outputs removed if the command is interrupted, keyed by guard id
+static PARTIAL_OUTPUTS: Mutex<Vec<(u64, Utf8PathBuf)>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Registration of a partially written output, unregistered on drop once the
+/// output is complete (or the command failed and cleaned up after itself)
+#[must_use = "the output is unregistered when the guard is dropped"]
+pub struct PartialOutput {
+    id: u64,
+}
+impl Drop for PartialOutput {
+    fn drop(&mut self) {
+        if let Ok(mut outputs) = PARTIAL_OUTPUTS.lock() {
+            outputs.retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+/// Removes the file or directory at `path` if the command is interrupted
+/// while the returned guard is alive
+pub fn remove_on_interrupt(path: &Utf8Path) -> PartialOutput {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut outputs) = PARTIAL_OUTPUTS.lock() {
+        outputs.push((id, path.to_path_buf()));
+    }
+    PartialOutput { id }
+}
+
+/// Removes every registered partial output, called on interrupt before the
+/// command future is dropped (which removes its temp dirs and clears spinners)
+pub fn cleanup() {
+    let outputs = match PARTIAL_OUTPUTS.lock() {
+        Ok(mut outputs) => std::mem::take(&mut *outputs),
+        Err(_) => return,
+    };
+
+    for (_, path) in outputs {
+        let res = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else if path.exists() {
+            fs::remove_file(&path)
+        } else {
+            continue;
+        };
+        match res {
+            Ok(_) => debug!("Removed partial output {path}"),
+            Err(e) => warn!("Failed removing partial output {path}: {e}"),
+        }
+    }
+}
@@ -3,6 +3,8 @@ use std::io::Write;
 use tabled::settings::{peaker::Priority, Width};
 use terminal_size::{terminal_size, Height as TerminalHeight, Width as TerminalWidth};
 
+use crate::styles::ColorTheme;
+
 pub fn init_logger(quiet: bool, verbose: u8) {
     let level = if quiet {
         log::Level::Error
@@ -15,6 +17,9 @@ pub fn init_logger(quiet: bool, verbose: u8) {
     };
 
     let mut builder = env_logger::builder();
+    if ColorTheme::current() == ColorTheme::Mono {
+        builder.write_style(env_logger::WriteStyle::Never);
+    }
 
     if level == log::Level::Trace {
         builder.filter_level(level.to_level_filter());
@@ -58,3 +63,20 @@ pub fn log_table(mut table: tabled::Table) {
     }
     info!("\n{table}\n");
 }
+
+/// Writes the value as a YAML document to stdout (unaffected by log level)
+pub fn print_yaml<T: ?Sized + serde::Serialize>(val: &T) {
+    match serde_yaml::to_string(val) {
+        Ok(yaml) => print!("{yaml}"),
+        // fall back to the json representation, which is also valid yaml
+        Err(_) => print_json(val),
+    }
+}
+
+/// Writes the value as a single line of JSON to stdout (unaffected by log level)
+pub fn print_json<T: ?Sized + serde::Serialize>(val: &T) {
+    println!(
+        "{}",
+        serde_json::to_string(val).unwrap_or_else(|_| serde_json::json!(val).to_string())
+    )
+}
@@ -1,3 +1,5 @@
+use std::ops::{Deref, DerefMut};
+
 use log::{debug, error, info, warn};
 use sideko_rest_api::{
     models::CliUpdateSeverityEnum, resources::cli::CheckUpdatesRequest, SidekoClient,
@@ -5,18 +7,62 @@ use sideko_rest_api::{
 
 use crate::result::{CliError, CliResult};
 
+pub(crate) mod api_version;
+pub(crate) mod archive;
 pub(crate) mod config;
 pub(crate) mod editor;
+pub(crate) mod git;
+pub(crate) mod interrupt;
 pub(crate) mod logging;
+pub(crate) mod openapi;
+pub(crate) mod parse_mode;
+pub(crate) mod patch;
+pub(crate) mod prompt;
+pub(crate) mod resolve;
 pub(crate) mod response;
+pub(crate) mod retry;
+pub(crate) mod sdk_config;
 pub(crate) mod spinner;
 pub(crate) mod tabled;
+pub(crate) mod throttle;
+pub(crate) mod time;
 pub(crate) mod url_builder;
 pub(crate) mod validators;
 
 /// Initializes SidekoClient using base url & api key from config environment
-pub(crate) fn get_sideko_client() -> SidekoClient {
-    let mut client = SidekoClient::default().with_base_url(&config::get_base_url());
+///
+/// The client holds a slot of the `--max-parallel-requests` limit until it is dropped
+/// (see `throttle::acquire`), clients of the same request flow share the slot
+pub(crate) async fn get_sideko_client() -> CliResult<ThrottledClient> {
+    let client = sideko_client_with_base_url(&config::get_base_url()?);
+    Ok(ThrottledClient {
+        client,
+        _permit: throttle::acquire().await,
+    })
+}
+
+/// SidekoClient holding a slot of the request limit, see `get_sideko_client`
+pub(crate) struct ThrottledClient {
+    client: SidekoClient,
+    _permit: throttle::Permit,
+}
+impl Deref for ThrottledClient {
+    type Target = SidekoClient;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+impl DerefMut for ThrottledClient {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.client
+    }
+}
+
+/// Same as `get_sideko_client` with an already resolved base url, for clients
+/// built inside request closures (e.g. once per retry attempt)
+pub(crate) fn sideko_client_with_base_url(base_url: &str) -> SidekoClient {
+    let mut client = SidekoClient::default().with_base_url(base_url);
     if let Some(key) = config::get_api_key() {
         client = client.with_api_key_auth(&key);
     }
@@ -29,11 +75,25 @@ pub async fn check_for_updates() -> CliResult<()> {
     let cli_version = env!("CARGO_PKG_VERSION").to_string();
     debug!("Checking for updates (CLI version: {cli_version})...");
 
-    let mut client = SidekoClient::default().with_base_url(&config::get_base_url());
-    let updates = client
-        .cli()
-        .check_updates(CheckUpdatesRequest { cli_version })
-        .await?;
+    let base_url = config::get_base_url()?;
+    // every invocation makes this request, so parallel CI jobs rely on the
+    // jittered backoff to avoid retrying in lockstep
+    let updates = retry::with_retries(
+        retry::DEFAULT_ATTEMPTS,
+        retry::Operation::Idempotent,
+        || {
+            let cli_version = cli_version.clone();
+            let base_url = base_url.clone();
+            async move {
+                let mut client = SidekoClient::default().with_base_url(&base_url);
+                client
+                    .cli()
+                    .check_updates(CheckUpdatesRequest { cli_version })
+                    .await
+            }
+        },
+    )
+    .await?;
 
     if updates.is_empty() {
         debug!("No updates!")
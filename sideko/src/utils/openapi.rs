@@ -0,0 +1,147 @@
+use std::{collections::BTreeSet, fs};
+
+use camino::Utf8Path;
+use serde_json::Value;
+
+use crate::result::{CliError, CliResult};
+
+use super::parse_mode::{self, ParseMode};
+
+/// HTTP methods that define an operation within an OpenAPI path item
+const OPERATION_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Fields allowed at the top-level of an OpenAPI 3.x or Swagger 2.0 specification,
+/// besides `x-` extensions
+const TOP_LEVEL_FIELDS: [&str; 20] = [
+    "openapi",
+    "swagger",
+    "info",
+    "jsonSchemaDialect",
+    "servers",
+    "paths",
+    "webhooks",
+    "components",
+    "security",
+    "tags",
+    "externalDocs",
+    "host",
+    "basePath",
+    "schemes",
+    "consumes",
+    "produces",
+    "definitions",
+    "parameters",
+    "responses",
+    "securityDefinitions",
+];
+
+/// Loads a JSON or YAML OpenAPI specification into a generic JSON value, authoring
+/// issues (duplicate keys, unknown top-level fields) are handled per the parse mode
+pub fn load(path: &Utf8Path) -> CliResult<Value> {
+    let (spec, issues) = load_with_issues(path)?;
+    ParseMode::current().check(&format!("OpenAPI {path}"), &issues)?;
+    Ok(spec)
+}
+
+/// Same as `load`, returning the authoring issues instead of handling them
+pub fn load_with_issues(path: &Utf8Path) -> CliResult<(Value, Vec<String>)> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| CliError::io_custom(format!("Failed reading OpenAPI from path: {path}"), e))?;
+
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let spec = parse(path, &content, is_json)?;
+
+    let mut issues = parse_mode::duplicate_keys(&content, is_json);
+    if let Some(fields) = spec.as_object() {
+        issues.extend(
+            fields
+                .keys()
+                .filter(|k| !k.starts_with("x-") && !TOP_LEVEL_FIELDS.contains(&k.as_str()))
+                .map(|k| format!("unknown top-level field `{k}`")),
+        );
+    }
+
+    Ok((spec, issues))
+}
+
+fn parse(path: &Utf8Path, content: &str, is_json: bool) -> CliResult<Value> {
+    if is_json {
+        serde_json::from_str(content).map_err(|e| {
+            CliError::general_debug(
+                format!("Failed parsing OpenAPI JSON from path: {path}"),
+                format!("{e:?}"),
+            )
+        })
+    } else {
+        serde_yaml::from_str(content).map_err(|e| {
+            CliError::general_debug(
+                format!("Failed parsing OpenAPI YAML from path: {path}"),
+                format!("{e:?}"),
+            )
+        })
+    }
+}
+
+/// Summary counts of an OpenAPI specification
+#[derive(Debug, serde::Serialize)]
+pub struct SpecStats {
+    pub paths: usize,
+    pub operations: usize,
+    pub schemas: usize,
+    pub tags: usize,
+}
+impl SpecStats {
+    pub fn from_spec(spec: &Value) -> Self {
+        let paths = spec
+            .get("paths")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+
+        // tags may be declared at the top-level or only used by operations
+        let mut tags: BTreeSet<String> = spec
+            .get("tags")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|t| t.get("name").and_then(Value::as_str))
+            .map(String::from)
+            .collect();
+
+        let mut operations = 0;
+        for item in paths.values() {
+            for method in OPERATION_METHODS {
+                if let Some(op) = item.get(method) {
+                    operations += 1;
+                    tags.extend(
+                        op.get("tags")
+                            .and_then(Value::as_array)
+                            .into_iter()
+                            .flatten()
+                            .filter_map(Value::as_str)
+                            .map(String::from),
+                    );
+                }
+            }
+        }
+
+        // OpenAPI 3.x `components.schemas`, Swagger 2.0 `definitions`
+        let schemas = spec
+            .pointer("/components/schemas")
+            .or_else(|| spec.get("definitions"))
+            .and_then(Value::as_object)
+            .map(|s| s.len())
+            .unwrap_or_default();
+
+        Self {
+            paths: paths.len(),
+            operations,
+            schemas,
+            tags: tags.len(),
+        }
+    }
+}
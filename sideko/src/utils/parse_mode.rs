@@ -0,0 +1,182 @@
+use std::{cell::RefCell, collections::HashSet, fmt};
+
+use log::warn;
+use serde::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+
+use crate::result::{CliError, CliResult};
+
+use super::config::ConfigKey;
+
+/// How authoring issues found while loading configs and specs (e.g. duplicate keys)
+/// are handled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseMode {
+    /// Issues are logged as warnings, the parsed value keeps the last duplicate
+    Lenient,
+    /// Issues fail the command
+    Strict,
+}
+impl ParseMode {
+    /// Mode configured via `--parse-strict`/`--parse-lenient` (or its env var),
+    /// lenient by default
+    pub fn current() -> Self {
+        let strict = ConfigKey::ParseStrict
+            .get_env()
+            .is_some_and(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false"));
+        if strict {
+            ParseMode::Strict
+        } else {
+            ParseMode::Lenient
+        }
+    }
+
+    /// Fails on any of the `issues` found in `source` in strict mode, warns otherwise
+    pub fn check(&self, source: &str, issues: &[String]) -> CliResult<()> {
+        if issues.is_empty() {
+            return Ok(());
+        }
+
+        match self {
+            ParseMode::Strict => Err(CliError::general(format!(
+                "{source} failed strict parsing: {} (use --parse-lenient to only warn)",
+                issues.join("; ")
+            ))),
+            ParseMode::Lenient => {
+                for issue in issues {
+                    warn!("{source}: {issue}");
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Paths of keys repeated within the same mapping of a JSON or YAML document,
+/// which most parsers silently collapse to the last occurrence. Malformed
+/// documents yield no paths, parsing reports those errors
+pub fn duplicate_keys(content: &str, is_json: bool) -> Vec<String> {
+    let found = RefCell::new(vec![]);
+    let scan = DuplicateScan {
+        path: String::new(),
+        found: &found,
+    };
+    let scanned = if is_json {
+        scan.deserialize(&mut serde_json::Deserializer::from_str(content))
+            .map_err(|e| e.to_string())
+    } else {
+        scan.deserialize(serde_yaml::Deserializer::from_str(content))
+            .map_err(|e| e.to_string())
+    };
+    match scanned {
+        Ok(_) => found.into_inner(),
+        Err(_) => vec![],
+    }
+}
+
+/// Walks a document without keeping it, recording duplicate keys in `found`
+struct DuplicateScan<'a> {
+    path: String,
+    found: &'a RefCell<Vec<String>>,
+}
+impl DuplicateScan<'_> {
+    fn child(&self, segment: &str) -> Self {
+        let path = if self.path.is_empty() || segment.starts_with('[') {
+            format!("{}{segment}", self.path)
+        } else {
+            format!("{}.{segment}", self.path)
+        };
+        DuplicateScan {
+            path,
+            found: self.found,
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for DuplicateScan<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DuplicateScan<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON or YAML value")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_bytes<E: de::Error>(self, _: &[u8]) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut idx = 0;
+        while seq
+            .next_element_seed(self.child(&format!("[{idx}]")))?
+            .is_some()
+        {
+            idx += 1;
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut seen = HashSet::new();
+        while let Some(key) = map.next_key::<serde_json::Value>()? {
+            let key = match key {
+                serde_json::Value::String(key) => key,
+                key => key.to_string(),
+            };
+            let child = self.child(&key);
+            if !seen.insert(key) {
+                self.found
+                    .borrow_mut()
+                    .push(format!("duplicate key `{}`", child.path));
+            }
+            map.next_value_seed(child)?;
+        }
+        Ok(())
+    }
+
+    /// YAML tagged values (e.g. `!include file.yaml`)
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<(), A::Error> {
+        let (_, variant) = data.variant::<de::IgnoredAny>()?;
+        variant.newtype_variant_seed(self)
+    }
+}
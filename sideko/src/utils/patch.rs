@@ -0,0 +1,54 @@
+/// Lines changed in a single file of a unified diff
+#[derive(Debug, Default)]
+pub struct FilePatch {
+    pub path: String,
+    /// Path before the change, differs from `path` for renames
+    pub old_path: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Parses a unified (git) diff into the lines added and removed per file
+pub fn parse(patch: &str) -> Vec<FilePatch> {
+    let mut files: Vec<FilePatch> = vec![];
+    for line in patch.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            // `a/<old path> b/<new path>`
+            let (old_path, path) = header
+                .rsplit_once(" b/")
+                .map(|(old, new)| (old.strip_prefix("a/").unwrap_or(old), new))
+                .unwrap_or((header, header));
+            files.push(FilePatch {
+                path: path.to_string(),
+                old_path: old_path.to_string(),
+                ..Default::default()
+            });
+        } else if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        } else if let Some(file) = files.last_mut() {
+            if let Some(added) = line.strip_prefix('+') {
+                file.added.push(added.to_string());
+            } else if let Some(removed) = line.strip_prefix('-') {
+                file.removed.push(removed.to_string());
+            }
+        }
+    }
+
+    files
+}
+
+/// `git diff --stat` style summary of the parsed files,
+/// e.g. `3 files changed, 10 insertions(+), 2 deletions(-)`
+pub fn stat_summary(files: &[FilePatch]) -> String {
+    let plural = |n: usize, singular: &str, plural: &str| {
+        format!("{n} {}", if n == 1 { singular } else { plural })
+    };
+    let added: usize = files.iter().map(|f| f.added.len()).sum();
+    let removed: usize = files.iter().map(|f| f.removed.len()).sum();
+    format!(
+        "{} changed, {}(+), {}(-)",
+        plural(files.len(), "file", "files"),
+        plural(added, "insertion", "insertions"),
+        plural(removed, "deletion", "deletions"),
+    )
+}
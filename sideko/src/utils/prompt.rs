@@ -0,0 +1,105 @@
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
+
+use log::{debug, warn};
+
+use crate::result::{CliError, CliResult};
+
+use super::config::ConfigKey;
+
+/// Set once a prompt timed out: its thread cannot be cancelled and keeps waiting for
+/// terminal input, so nothing else may read from the terminal afterwards
+static ABANDONED_PROMPT: AtomicBool = AtomicBool::new(false);
+
+/// Whether the terminal can be handed to a prompt or an interactive child process
+/// (e.g. an editor), false once a timed out prompt still waits for its input
+pub fn terminal_available() -> bool {
+    !ABANDONED_PROMPT.load(Ordering::SeqCst)
+}
+
+/// Timeout for prompt input set via `--prompt-timeout` or `SIDEKO_PROMPT_TIMEOUT`
+fn prompt_timeout() -> Option<Duration> {
+    ConfigKey::PromptTimeout
+        .get_env()
+        .and_then(|secs| secs.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+/// Whether prompts take their default answer on timeout instead of aborting
+fn use_default_on_timeout() -> bool {
+    ConfigKey::PromptTimeoutDefault
+        .get_env()
+        .is_some_and(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false"))
+}
+
+/// Runs an interactive prompt, honoring `--prompt-timeout`
+///
+/// When no input is received within the timeout the `default` answer is taken if
+/// `--prompt-timeout-default` is set (and the prompt has a default), otherwise the
+/// command aborts rather than hanging an automated job.
+pub fn prompt<T, F>(prompt: F, default: Option<T>) -> CliResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> inquire::InquireResult<T> + Send + 'static,
+{
+    if !terminal_available() {
+        // a new prompt would race the abandoned one for input
+        return match default {
+            Some(default) if use_default_on_timeout() => {
+                warn!("An earlier prompt timed out, continuing with the default answer");
+                Ok(default)
+            }
+            _ => Err(CliError::general(
+                "An earlier prompt timed out (--prompt-timeout) and still holds the terminal, aborting",
+            )),
+        };
+    }
+    let Some(timeout) = prompt_timeout() else {
+        return Ok(prompt()?);
+    };
+
+    // inquire blocks on terminal input, so the prompt is raced against the timeout on its own thread
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(prompt());
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(res) => Ok(res?),
+        Err(e) => {
+            debug!("Prompt did not complete: {e:?}");
+            abandon_terminal();
+            match default {
+                Some(default) if use_default_on_timeout() => {
+                    warn!(
+                        "No prompt input within {}s, continuing with the default answer",
+                        timeout.as_secs()
+                    );
+                    Ok(default)
+                }
+                _ => Err(CliError::general(format!(
+                    "No prompt input within {}s (--prompt-timeout), aborting",
+                    timeout.as_secs()
+                ))),
+            }
+        }
+    }
+}
+
+/// Hands the terminal back from a prompt that timed out: inquire leaves it in raw
+/// mode while its thread is blocked reading input
+fn abandon_terminal() {
+    ABANDONED_PROMPT.store(true, Ordering::SeqCst);
+    if let Err(e) = crossterm::terminal::disable_raw_mode() {
+        debug!("Failed restoring terminal mode: {e:?}");
+    }
+    // the prompt was left mid-line
+    let _ = writeln!(io::stderr());
+}
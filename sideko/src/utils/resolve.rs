@@ -0,0 +1,56 @@
+use log::debug;
+use regex::Regex;
+use sideko_rest_api::models::Api;
+
+use crate::result::{CliError, CliResult};
+
+use super::get_sideko_client;
+
+/// Whether the reference looks like a UUID (e.g. an API id)
+fn is_uuid(reference: &str) -> bool {
+    Regex::new(r"(?i)^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$")
+        .expect("invalid uuid regex pattern")
+        .is_match(reference)
+}
+
+/// Resolves an API from either its id or its name
+///
+/// Ids are matched first when the reference looks like a UUID, otherwise the
+/// reference is matched against API names. Errors if nothing (or more than one API) matches.
+pub async fn resolve_api(reference: &str) -> CliResult<Api> {
+    let mut client = get_sideko_client().await?;
+    let apis = client.api().list().await?;
+
+    let by_id = is_uuid(reference);
+    let mut matches: Vec<Api> = apis
+        .into_iter()
+        .filter(|a| {
+            if by_id {
+                a.id.eq_ignore_ascii_case(reference) || a.name == reference
+            } else {
+                a.name == reference
+            }
+        })
+        .collect();
+
+    match matches.len() {
+        0 => Err(CliError::general(format!(
+            "No API found with name or id `{reference}`, run `sideko api list` to see available APIs"
+        ))),
+        1 => {
+            let api = matches.remove(0);
+            debug!("Resolved `{reference}` to API {} ({})", api.name, api.id);
+            Ok(api)
+        }
+        _ => Err(CliError::general_debug(
+            format!("`{reference}` matches more than one API, use the API id instead"),
+            format!(
+                "Matching APIs: {:?}",
+                matches
+                    .iter()
+                    .map(|a| format!("{} ({})", a.name, a.id))
+                    .collect::<Vec<_>>()
+            ),
+        )),
+    }
+}
@@ -1,3 +1,4 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use camino::Utf8PathBuf;
 use regex::Regex;
 use sideko_rest_api::BinaryResponse;
@@ -23,3 +24,62 @@ pub fn extract_filename(res: &BinaryResponse) -> Option<Utf8PathBuf> {
     // Convert filename to Utf8PathBuf
     Utf8PathBuf::from_str(name_match.as_str()).ok()
 }
+
+/// Single metric of a `Server-Timing` header, e.g. `codegen;dur=1520.4;desc="Code generation"`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerTiming {
+    pub name: String,
+    /// Duration in milliseconds
+    pub duration: Option<f64>,
+    pub description: Option<String>,
+}
+
+/// Parses the `Server-Timing` headers of the response (https://www.w3.org/TR/server-timing/)
+///
+/// Malformed parameters are ignored, metrics without a name are skipped
+pub fn extract_server_timing(res: &BinaryResponse) -> Vec<ServerTiming> {
+    res.headers
+        .get_all("server-timing")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|metric| {
+            let mut params = metric.split(';').map(str::trim);
+            let name = params.next().filter(|n| !n.is_empty())?;
+            let mut timing = ServerTiming {
+                name: name.to_string(),
+                duration: None,
+                description: None,
+            };
+            for param in params {
+                match param.split_once('=') {
+                    Some((key, val)) if key.trim().eq_ignore_ascii_case("dur") => {
+                        timing.duration = val.trim().parse().ok();
+                    }
+                    Some((key, val)) if key.trim().eq_ignore_ascii_case("desc") => {
+                        timing.description = Some(val.trim().trim_matches('"').to_string());
+                    }
+                    _ => {}
+                }
+            }
+            Some(timing)
+        })
+        .collect()
+}
+
+/// Extracts the SHA-256 digest of the response body from the `Content-Digest`
+/// (`sha-256=:<base64>:`, RFC 9530) or legacy `Digest` (`SHA-256=<base64>`, RFC 3230) header
+pub fn extract_sha256_digest(res: &BinaryResponse) -> Option<Vec<u8>> {
+    ["content-digest", "digest"]
+        .iter()
+        .flat_map(|header| res.headers.get_all(*header).iter())
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .find_map(|digest| {
+            let (algorithm, val) = digest.trim().split_once('=')?;
+            if !algorithm.trim().eq_ignore_ascii_case("sha-256") {
+                return None;
+            }
+            BASE64.decode(val.trim().trim_matches(':')).ok()
+        })
+}
@@ -0,0 +1,204 @@
+use std::{future::Future, time::Duration};
+
+use chrono::{DateTime, Utc};
+
+use log::{debug, warn};
+use rand::Rng;
+
+use super::{config::ConfigKey, throttle};
+
+/// Default delay before the first retry, in milliseconds
+pub const DEFAULT_BASE_DELAY_MS: u64 = 500;
+/// Default upper bound of the delay between retries, in milliseconds
+pub const DEFAULT_MAX_DELAY_MS: u64 = 10_000;
+/// Attempts made (including the first) for retried requests
+pub const DEFAULT_ATTEMPTS: u32 = 3;
+/// Default retries of SDK generate/update requests
+pub const DEFAULT_SDK_RETRIES: u32 = 2;
+
+/// Response statuses of temporary server-side failures
+const RETRYABLE_STATUSES: [u16; 4] = [429, 502, 503, 504];
+
+/// Attempts made (including the first) for SDK generate/update requests, configured
+/// via `--retries` (or its env var)
+pub fn sdk_attempts() -> u32 {
+    let key = ConfigKey::Retries;
+    let retries = key
+        .get_env()
+        .and_then(|v| match v.trim().parse::<u32>() {
+            Ok(retries) => Some(retries),
+            Err(_) => {
+                warn!("Ignoring invalid {key} value `{v}`, expected a non-negative integer");
+                None
+            }
+        })
+        .unwrap_or(DEFAULT_SDK_RETRIES);
+
+    retries.saturating_add(1)
+}
+
+/// Exponential backoff between retries of a failed request
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_millis(DEFAULT_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+            jitter: true,
+        }
+    }
+}
+impl Backoff {
+    /// Backoff configured via `--retry-base-delay`, `--retry-max-delay`, and
+    /// `--retry-jitter` (or their env vars), falling back to the defaults
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let millis = |key: ConfigKey| {
+            key.get_env().and_then(|v| match v.trim().parse::<u64>() {
+                Ok(ms) => Some(Duration::from_millis(ms)),
+                Err(_) => {
+                    warn!("Ignoring invalid {key} value `{v}`, expected milliseconds");
+                    None
+                }
+            })
+        };
+
+        let base_delay = millis(ConfigKey::RetryBaseDelay).unwrap_or(default.base_delay);
+        let max_delay = millis(ConfigKey::RetryMaxDelay).unwrap_or(default.max_delay);
+        let jitter = ConfigKey::RetryJitter
+            .get_env()
+            .map(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false"))
+            .unwrap_or(default.jitter);
+
+        Self {
+            base_delay: base_delay.min(max_delay),
+            max_delay,
+            jitter,
+        }
+    }
+
+    /// Delay before retry number `retry` (starting at 0): the base delay doubled
+    /// per retry and capped at the max delay
+    ///
+    /// With jitter the delay is drawn uniformly from the upper half of that
+    /// range, so parallel clients failing together do not retry in lockstep.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        if !self.jitter {
+            return exp;
+        }
+
+        let half = exp / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+    }
+}
+
+/// Whether the error is likely transient (e.g. a dropped connection, timeout, or
+/// overloaded server) and the request worth retrying. Client errors (e.g. 400, 401,
+/// 422) are never retried
+pub fn is_transient(err: &sideko_rest_api::Error) -> bool {
+    match err {
+        sideko_rest_api::Error::Request(_) | sideko_rest_api::Error::Io(_) => true,
+        sideko_rest_api::Error::Api(e) => RETRYABLE_STATUSES.contains(&e.status_code.as_u16()),
+        _ => false,
+    }
+}
+
+/// Whether the request certainly failed before reaching the server (e.g. connection
+/// refused), so retrying it cannot repeat any side effect
+pub fn is_pre_send(err: &sideko_rest_api::Error) -> bool {
+    matches!(err, sideko_rest_api::Error::Request(e) if e.is_connect())
+}
+
+/// Whether repeating a request may repeat its side effects
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    /// Reads, or requests whose repetition has no further effect
+    Idempotent,
+    /// Requests creating or changing server state (e.g. SDK updates, deployments)
+    NonIdempotent,
+}
+impl Operation {
+    /// Whether the failed request may be retried. With `--retry-idempotent-only`
+    /// non-idempotent requests are only retried when they never reached the server,
+    /// as the Sideko API client does not send idempotency keys
+    pub fn retryable(&self, err: &sideko_rest_api::Error) -> bool {
+        match self {
+            Operation::Idempotent => is_transient(err),
+            Operation::NonIdempotent if idempotent_only() => is_pre_send(err),
+            Operation::NonIdempotent => is_transient(err),
+        }
+    }
+}
+
+/// Whether `--retry-idempotent-only` (or its env var) is set
+pub fn idempotent_only() -> bool {
+    ConfigKey::RetryIdempotentOnly
+        .get_env()
+        .is_some_and(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false"))
+}
+
+/// Delay requested by the server's `Retry-After` header, either in seconds
+/// or as an HTTP date
+pub fn retry_after(err: &sideko_rest_api::Error) -> Option<Duration> {
+    let sideko_rest_api::Error::Api(e) = err else {
+        return None;
+    };
+    let value = e.headers.get("retry-after")?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// Runs `request`, retrying failures retryable for the `operation` up to `attempts`
+/// times in total with the configured backoff between attempts (or the server's
+/// `Retry-After`, capped at the backoff's max delay)
+///
+/// Each attempt holds a slot of the global request limit (see `throttle::acquire`),
+/// which is released while waiting to retry.
+pub async fn with_retries<T, F, Fut>(
+    attempts: u32,
+    operation: Operation,
+    mut request: F,
+) -> Result<T, sideko_rest_api::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sideko_rest_api::Error>>,
+{
+    let backoff = Backoff::from_env();
+    let mut retry = 0;
+    loop {
+        let res = {
+            let _permit = throttle::acquire().await;
+            request().await
+        };
+        match res {
+            Err(e) if operation.retryable(&e) && retry + 1 < attempts => {
+                // a far off `Retry-After` is capped so a command cannot hang on it
+                let delay = retry_after(&e)
+                    .map(|delay| delay.min(backoff.max_delay))
+                    .unwrap_or_else(|| backoff.delay(retry));
+                debug!(
+                    "Request failed ({e}), retrying in {}ms ({}/{})",
+                    delay.as_millis(),
+                    retry + 1,
+                    attempts - 1
+                );
+                tokio::time::sleep(delay).await;
+                retry += 1;
+            }
+            res => return res,
+        }
+    }
+}
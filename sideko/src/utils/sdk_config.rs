@@ -0,0 +1,675 @@
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, Read, Write},
+    sync::Arc,
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use log::{debug, info, warn};
+use serde_yaml::Value;
+use sideko_rest_api::UploadFile;
+use tempfile::TempDir;
+use url::{Position, Url};
+
+use crate::{
+    result::{CliError, CliResult},
+    utils::parse_mode::{self, ParseMode},
+};
+
+/// Extensions of files picked up from an SDK config directory
+const CONFIG_EXTENSIONS: [&str; 3] = ["yaml", "yml", "json"];
+
+/// Default number of directory levels scanned for configs
+pub const DEFAULT_MAX_DEPTH: usize = 8;
+
+/// `--config` value reading the SDK config from stdin
+pub const STDIN_CONFIG: &str = "-";
+
+/// `--config` value, see `validators::validate_config_source`
+#[derive(Debug, Clone)]
+pub enum ConfigArg {
+    /// Local config file or directory, `-` for stdin
+    Path(Utf8PathBuf),
+    /// Remote config fetched over `http(s)`, e.g. a signed artifact store URL
+    Url(Url),
+}
+impl Display for ConfigArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigArg::Path(path) => write!(f, "{path}"),
+            ConfigArg::Url(url) => write!(f, "{}", redact_url(url)),
+        }
+    }
+}
+
+/// URL without its query and fragment, which may carry a signature
+fn redact_url(url: &Url) -> &str {
+    &url[..Position::AfterPath]
+}
+
+/// SDK config read from stdin, a URL, or SDK metadata, buffered into a temporary file so it is
+/// checked and uploaded like a config file. The file is removed when dropped
+#[derive(Debug)]
+pub struct BufferedConfig {
+    origin: String,
+    path: Utf8PathBuf,
+    _dir: TempDir,
+}
+impl BufferedConfig {
+    /// Reads stdin to the end, failing if it is not a valid YAML (or JSON) config
+    pub fn from_stdin() -> CliResult<Self> {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|e| CliError::io_custom("Failed reading SDK config from stdin", e))?;
+
+        Self::buffer("stdin".into(), content)
+    }
+
+    /// Fetches the config, failing on an error status or if it is not a valid
+    /// YAML (or JSON) config
+    pub async fn from_url(url: &Url) -> CliResult<Self> {
+        let origin = redact_url(url).to_string();
+        let fetch_err = |e: reqwest::Error| {
+            CliError::general_debug(
+                format!("Failed fetching SDK config from {origin}"),
+                // the url is omitted as its query may carry a signature
+                format!("{:?}", e.without_url()),
+            )
+        };
+
+        debug!("Fetching SDK config from {origin}");
+        let content = reqwest::get(url.clone())
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(fetch_err)?
+            .bytes()
+            .await
+            .map_err(fetch_err)?;
+        let content = String::from_utf8(content.to_vec()).map_err(|e| {
+            CliError::general_debug(
+                format!("SDK config fetched from {origin} is not UTF-8"),
+                format!("{e:?}"),
+            )
+        })?;
+
+        Self::buffer(origin, content)
+    }
+
+    /// Buffers a config embedded in another document (e.g. the SDK metadata)
+    pub fn from_value(origin: String, config: &serde_json::Value) -> CliResult<Self> {
+        let content = serde_json::to_string_pretty(config).map_err(|e| {
+            CliError::general_debug(
+                format!("Failed serializing SDK config from {origin}"),
+                format!("{e:?}"),
+            )
+        })?;
+
+        Self::buffer(origin, content)
+    }
+
+    fn buffer(origin: String, content: String) -> CliResult<Self> {
+        if content.trim().is_empty() {
+            return Err(CliError::general(format!(
+                "No SDK config received from {origin}"
+            )));
+        }
+        parse_config(&content, &origin)?;
+
+        let dir = TempDir::new()
+            .map_err(|e| CliError::io_custom("Failed creating temporary directory", e))?;
+        let path = Utf8PathBuf::try_from(dir.path().join("sdk-config.yaml")).map_err(|e| {
+            CliError::general_debug("Temporary directory path is not UTF-8", format!("{e:?}"))
+        })?;
+        fs::write(&path, content).map_err(|e| {
+            CliError::io_custom(format!("Failed buffering SDK config from {origin}"), e)
+        })?;
+        debug!("Buffered SDK config from {origin} into {path}");
+
+        Ok(Self {
+            origin,
+            path,
+            _dir: dir,
+        })
+    }
+
+    /// Where the config was read from: `stdin`, the URL without its query, or the
+    /// document it was embedded in
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+}
+
+/// Where the SDK config passed to `--config` is loaded from
+#[derive(Debug, Clone)]
+pub enum SdkConfigSource {
+    /// Single config file
+    File(Utf8PathBuf),
+    /// Directory of config files, scanned recursively up to `max_depth` levels
+    /// (symlinked directories are not followed), merged in path order
+    Dir { path: Utf8PathBuf, max_depth: usize },
+    /// Config read from stdin (`--config -`) or fetched from a URL
+    Buffered(Arc<BufferedConfig>),
+}
+impl SdkConfigSource {
+    /// Source of the `--config` value, fetching remote configs
+    pub async fn from_arg(arg: &ConfigArg, max_depth: usize) -> CliResult<Self> {
+        match arg {
+            ConfigArg::Path(path) => Self::from_path(path, max_depth),
+            ConfigArg::Url(url) => Ok(Self::Buffered(Arc::new(
+                BufferedConfig::from_url(url).await?,
+            ))),
+        }
+    }
+
+    /// Source of a local `--config` value, `-` reads the config from stdin
+    pub fn from_path(path: &Utf8Path, max_depth: usize) -> CliResult<Self> {
+        let source = if path.as_str() == STDIN_CONFIG {
+            Self::Buffered(Arc::new(BufferedConfig::from_stdin()?))
+        } else if path.is_dir() {
+            Self::Dir {
+                path: path.into(),
+                max_depth,
+            }
+        } else {
+            Self::File(path.into())
+        };
+
+        Ok(source)
+    }
+
+    /// Config files making up the source, sorted by path
+    pub fn files(&self) -> CliResult<Vec<Utf8PathBuf>> {
+        match self {
+            SdkConfigSource::File(path) => Ok(vec![path.clone()]),
+            SdkConfigSource::Buffered(buffered) => Ok(vec![buffered.path().to_path_buf()]),
+            SdkConfigSource::Dir { path, max_depth } => {
+                let mut files = vec![];
+                let mut dirs = vec![(path.clone(), 1)];
+                while let Some((dir, depth)) = dirs.pop() {
+                    if depth > *max_depth {
+                        return Err(CliError::general(format!(
+                            "Config directory {path} is nested deeper than --max-depth {max_depth} at {dir}"
+                        )));
+                    }
+
+                    for entry in dir.read_dir_utf8().map_err(|e| {
+                        CliError::io_custom(format!("Failed reading config directory {dir}"), e)
+                    })? {
+                        let entry = entry?;
+                        // file_type does not follow symlinks, preventing directory cycles
+                        let file_type = entry.file_type()?;
+                        let entry_path = entry.into_path();
+                        if file_type.is_dir() {
+                            dirs.push((entry_path, depth + 1));
+                        } else if entry_path.is_file()
+                            && entry_path
+                                .extension()
+                                .is_some_and(|ext| CONFIG_EXTENSIONS.contains(&ext))
+                        {
+                            files.push(entry_path);
+                        }
+                    }
+                }
+                files.sort();
+                Ok(files)
+            }
+        }
+    }
+
+    /// Loads the config, merging directory configs into one
+    pub fn load(&self) -> CliResult<Value> {
+        let files = self.files()?;
+        if let SdkConfigSource::Dir { path, .. } = self {
+            if files.is_empty() {
+                warn!("Config directory {path} does not contain any .yaml, .yml, or .json files");
+            }
+        }
+
+        let mut merged = Value::Mapping(Default::default());
+        for path in files {
+            debug!("Merging config {path}");
+            merge(&mut merged, read_config(&path)?);
+        }
+        Ok(merged)
+    }
+
+    /// Loads the config as a file ready for upload, merging directory configs into one
+    pub fn upload_file(&self) -> CliResult<UploadFile> {
+        match self {
+            SdkConfigSource::File(path) => UploadFile::from_path(path.as_str()).map_err(|e| {
+                CliError::io_custom(format!("Failed reading config from path: {path}"), e)
+            }),
+            SdkConfigSource::Buffered(buffered) => UploadFile::from_path(buffered.path().as_str())
+                .map_err(|e| {
+                    CliError::io_custom(
+                        format!(
+                            "Failed reading SDK config buffered from {}",
+                            buffered.origin()
+                        ),
+                        e,
+                    )
+                }),
+            SdkConfigSource::Dir { .. } => upload_value(&self.load()?),
+        }
+    }
+
+    /// Same as `upload_file`, with the `overrides` set in the config and the `redact`
+    /// key paths removed from the result
+    pub fn upload_file_with_overrides(
+        &self,
+        overrides: &[ConfigOverride],
+        redact: &[KeyPath],
+    ) -> CliResult<UploadFile> {
+        if overrides.is_empty() && redact.is_empty() {
+            return self.upload_file();
+        }
+        if redact.is_empty() {
+            if let Some(patched) = self.patched(overrides)? {
+                return upload_str(&patched);
+            }
+        }
+
+        let mut config = self.load()?;
+        for config_override in overrides {
+            config_override.apply(&mut config);
+        }
+        for path in redact {
+            let removed = path.remove(&mut config);
+            if removed == 0 {
+                warn!("--redact-key `{path}` did not match anything in the SDK config");
+            } else {
+                info!("Redacted `{path}` from the SDK config ({removed} value(s) removed)");
+            }
+        }
+        upload_value(&config)
+    }
+
+    /// Config file content with the `overrides` patched in line by line, so comments
+    /// (including the schema version stamp) are kept. `None` when the config is a
+    /// directory or is not laid out as block style YAML
+    fn patched(&self, overrides: &[ConfigOverride]) -> CliResult<Option<String>> {
+        let path = match self {
+            SdkConfigSource::File(path) => path.as_path(),
+            SdkConfigSource::Buffered(buffered) => buffered.path(),
+            SdkConfigSource::Dir { .. } => return Ok(None),
+        };
+        let content = fs::read_to_string(path).map_err(|e| {
+            CliError::io_custom(format!("Failed reading config from path: {path}"), e)
+        })?;
+        let Ok(mut expected) = serde_yaml::from_str::<Value>(&content) else {
+            return Ok(None);
+        };
+
+        let mut patched = content;
+        for config_override in overrides {
+            config_override.apply(&mut expected);
+            match config_override.patch(&patched) {
+                Some(next) => patched = next,
+                None => {
+                    debug!("Could not patch `{config_override}` into {path}, rewriting the config");
+                    return Ok(None);
+                }
+            }
+        }
+
+        if serde_yaml::from_str::<Value>(&patched).is_ok_and(|config| config == expected) {
+            Ok(Some(patched))
+        } else {
+            debug!("Patching {path} changed more than the overrides, rewriting the config");
+            Ok(None)
+        }
+    }
+}
+
+/// String value set at a key path of the SDK config, e.g. `language.go.module`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigOverride {
+    path: Vec<String>,
+    value: String,
+}
+impl ConfigOverride {
+    pub fn new(path: Vec<String>, value: String) -> Self {
+        Self { path, value }
+    }
+
+    /// Sets the value, creating missing mappings along the path
+    fn apply(&self, config: &mut Value) {
+        let overlay =
+            self.path
+                .iter()
+                .rev()
+                .fold(Value::String(self.value.clone()), |value, key| {
+                    let mut mapping = serde_yaml::Mapping::new();
+                    mapping.insert(key.as_str().into(), value);
+                    mapping.into()
+                });
+        merge(config, overlay);
+    }
+
+    /// Sets the value in block style YAML content, leaving every other line untouched.
+    /// `None` when a mapping on the path is not written in block style
+    fn patch(&self, content: &str) -> Option<String> {
+        let newline = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let scalar = serde_yaml::to_string(&self.value).ok()?;
+        let scalar = scalar.trim_end();
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+        // lines of the mapping being searched, and the indent of its parent key
+        let (mut start, mut end) = (0, lines.len());
+        let mut parent_indent = None;
+        for (depth, key) in self.path.iter().enumerate() {
+            let child_indent = lines[start..end].iter().find_map(|l| content_indent(l));
+            let found = child_indent.and_then(|indent| {
+                (start..end).find(|&i| {
+                    content_indent(&lines[i]) == Some(indent) && key_value(&lines[i], key).is_some()
+                })
+            });
+
+            let Some((i, indent)) = found.zip(child_indent) else {
+                // append the missing keys at the end of the mapping
+                let indent = child_indent.unwrap_or(parent_indent.map_or(0, |p| p + 2));
+                let at = (start..end)
+                    .rev()
+                    .find(|&i| content_indent(&lines[i]).is_some())
+                    .map_or(start, |i| i + 1);
+                let missing = &self.path[depth..];
+                let added = missing.iter().enumerate().map(|(n, key)| {
+                    let pad = " ".repeat(indent + 2 * n);
+                    if n + 1 == missing.len() {
+                        format!("{pad}{key}: {scalar}")
+                    } else {
+                        format!("{pad}{key}:")
+                    }
+                });
+                lines.splice(at..at, added);
+                break;
+            };
+
+            let value = key_value(&lines[i], key)?;
+            if depth + 1 == self.path.len() {
+                let comment = inline_comment(value);
+                lines[i] = format!("{}{key}: {scalar}{comment}", " ".repeat(indent));
+                break;
+            }
+            if !value.is_empty() && !value.starts_with('#') {
+                // flow style mapping or a scalar
+                return None;
+            }
+            start = i + 1;
+            end = (start..end)
+                .find(|&j| content_indent(&lines[j]).is_some_and(|ind| ind <= indent))
+                .unwrap_or(end);
+            parent_indent = Some(indent);
+        }
+
+        let mut patched = lines.join(newline);
+        if content.ends_with('\n') {
+            patched.push_str(newline);
+        }
+        Some(patched)
+    }
+}
+impl Display for ConfigOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.join("."))
+    }
+}
+
+/// String value at a key path of the config
+pub fn lookup_str<'a>(config: &'a Value, path: &[String]) -> Option<&'a str> {
+    path.iter()
+        .try_fold(config, |node, key| node.get(key.as_str()))?
+        .as_str()
+}
+
+/// Indent of a line with content, `None` for blank and comment lines
+fn content_indent(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        None
+    } else {
+        Some(line.len() - trimmed.len())
+    }
+}
+
+/// Text after `key:` when the line is a mapping entry for `key`
+fn key_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let trimmed = line.trim_start();
+    let rest = [format!("\"{key}\""), format!("'{key}'"), key.to_string()]
+        .iter()
+        .find_map(|k| trimmed.strip_prefix(k.as_str()))?
+        .strip_prefix(':')?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// Trailing comment of an entry value, with its leading space
+fn inline_comment(value: &str) -> String {
+    if value.starts_with('#') {
+        return format!(" {value}");
+    }
+    let after_quote = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => value[1..].find(quote).map_or(value.len(), |i| i + 2),
+        _ => 0,
+    };
+    value[after_quote..]
+        .find(" #")
+        .map_or_else(String::new, |i| value[after_quote + i..].to_string())
+}
+
+/// Segment of a `KeyPath`
+#[derive(Debug, Clone, PartialEq)]
+enum KeySegment {
+    Key(String),
+    Index(usize),
+    /// `*`, every key of a mapping or item of a sequence
+    Wildcard,
+}
+
+/// JSONPath-like location of values in the SDK config, e.g. `$.servers[0].url`
+/// or `languages.*.auth` (the leading `$.` is optional)
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyPath {
+    raw: String,
+    segments: Vec<KeySegment>,
+}
+impl KeyPath {
+    /// Parses the path, used as a clap value parser
+    pub fn parse(arg: &str) -> Result<Self, String> {
+        let trimmed = arg.trim();
+        let body = trimmed
+            .strip_prefix("$.")
+            .or_else(|| trimmed.strip_prefix('$'))
+            .unwrap_or(trimmed);
+
+        let mut segments = vec![];
+        for part in body.split('.') {
+            let (key, mut rest) = match part.find('[') {
+                Some(i) => part.split_at(i),
+                None => (part, ""),
+            };
+            match key {
+                "" if rest.is_empty() => {
+                    return Err(format!("Invalid key path `{arg}`: empty segment"))
+                }
+                "" => {}
+                "*" => segments.push(KeySegment::Wildcard),
+                key => segments.push(KeySegment::Key(key.to_string())),
+            }
+
+            while !rest.is_empty() {
+                let Some((index, remaining)) =
+                    rest.strip_prefix('[').and_then(|r| r.split_once(']'))
+                else {
+                    return Err(format!("Invalid key path `{arg}`: unclosed `[`"));
+                };
+                segments.push(match index {
+                    "*" => KeySegment::Wildcard,
+                    index => KeySegment::Index(index.parse().map_err(|_| {
+                        format!("Invalid key path `{arg}`: `{index}` is not an index")
+                    })?),
+                });
+                rest = remaining;
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(format!("Invalid key path `{arg}`: no keys"));
+        }
+        Ok(Self {
+            raw: trimmed.to_string(),
+            segments,
+        })
+    }
+
+    /// Removes every value at the path, returning how many were removed
+    pub fn remove(&self, config: &mut Value) -> usize {
+        remove_at(config, &self.segments)
+    }
+}
+impl std::fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+fn remove_at(val: &mut Value, segments: &[KeySegment]) -> usize {
+    let Some((segment, rest)) = segments.split_first() else {
+        return 0;
+    };
+
+    match (segment, val) {
+        (KeySegment::Key(key), Value::Mapping(map)) if rest.is_empty() => {
+            usize::from(map.remove(key.as_str()).is_some())
+        }
+        (KeySegment::Key(key), Value::Mapping(map)) => map
+            .get_mut(key.as_str())
+            .map(|child| remove_at(child, rest))
+            .unwrap_or_default(),
+        (KeySegment::Index(i), Value::Sequence(seq)) if rest.is_empty() => {
+            if *i < seq.len() {
+                seq.remove(*i);
+                1
+            } else {
+                0
+            }
+        }
+        (KeySegment::Index(i), Value::Sequence(seq)) => seq
+            .get_mut(*i)
+            .map(|child| remove_at(child, rest))
+            .unwrap_or_default(),
+        (KeySegment::Wildcard, Value::Mapping(map)) if rest.is_empty() => {
+            let removed = map.len();
+            map.clear();
+            removed
+        }
+        (KeySegment::Wildcard, Value::Mapping(map)) => {
+            map.values_mut().map(|child| remove_at(child, rest)).sum()
+        }
+        (KeySegment::Wildcard, Value::Sequence(seq)) if rest.is_empty() => {
+            let removed = seq.len();
+            seq.clear();
+            removed
+        }
+        (KeySegment::Wildcard, Value::Sequence(seq)) => {
+            seq.iter_mut().map(|child| remove_at(child, rest)).sum()
+        }
+        _ => 0,
+    }
+}
+
+/// Writes the exact config bytes that will be uploaded to `dest`, or to stdout
+/// when no path is given
+pub fn print_upload(config: &UploadFile, dest: Option<&Utf8Path>) -> CliResult<()> {
+    match dest {
+        Some(path) => {
+            fs::write(path, &config.content).map_err(|e| {
+                CliError::io_custom(format!("Failed writing resolved SDK config to {path}"), e)
+            })?;
+            info!("Wrote resolved SDK config to {path}");
+        }
+        None => {
+            let mut stdout = io::stdout();
+            stdout.write_all(&config.content)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the config to a temporary file and loads it for upload
+fn upload_value(config: &Value) -> CliResult<UploadFile> {
+    let config_str = serde_yaml::to_string(config)
+        .map_err(|e| CliError::general_debug("Failed serializing SDK config", format!("{e:?}")))?;
+    upload_str(&config_str)
+}
+
+fn upload_str(config_str: &str) -> CliResult<UploadFile> {
+    let temp_dir = TempDir::new()
+        .map_err(|e| CliError::io_custom("Failed creating temporary directory", e))?;
+    let config_path = temp_dir.path().join("sdk-config.yaml");
+    fs::write(&config_path, config_str)
+        .map_err(|e| CliError::io_custom("Failed writing SDK config to temporary file", e))?;
+    UploadFile::from_path(&config_path.to_string_lossy())
+        .map_err(|e| CliError::io_custom("Failed reading SDK config from temporary file", e))
+}
+
+fn read_config(path: &Utf8Path) -> CliResult<Value> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| CliError::io_custom(format!("Failed reading config from path: {path}"), e))?;
+    parse_config(&content, &format!("path: {path}"))
+}
+
+/// Parses a config, `source` describes where it was read from in errors.
+/// Duplicate keys are handled per the parse mode
+fn parse_config(content: &str, source: &str) -> CliResult<Value> {
+    let parse_err = |e| {
+        CliError::general_debug(
+            format!("Failed parsing config from {source}"),
+            format!("{e:?}"),
+        )
+    };
+
+    let duplicates = parse_mode::duplicate_keys(content, false);
+    if duplicates.is_empty() {
+        // yaml is a superset of json, so json configs parse as well
+        return serde_yaml::from_str(content).map_err(parse_err);
+    }
+
+    ParseMode::current().check(&format!("SDK config from {source}"), &duplicates)?;
+    // yaml mappings reject duplicate keys, json objects keep the last occurrence
+    let lenient: serde_json::Value = serde_yaml::from_str(content).map_err(parse_err)?;
+    serde_yaml::to_value(lenient).map_err(parse_err)
+}
+
+/// Deep merges `other` into `base`, mappings are merged key by key and
+/// any other value in `other` replaces the value in `base`
+fn merge(base: &mut Value, other: Value) {
+    match (base, other) {
+        (Value::Mapping(base), Value::Mapping(other)) => {
+            for (key, val) in other {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, val),
+                    None => {
+                        base.insert(key, val);
+                    }
+                }
+            }
+        }
+        (_, Value::Null) => {}
+        (base, other) => *base = other,
+    }
+}
@@ -1,8 +1,19 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use log::{error, info, log_enabled, warn};
 
-use crate::styles::{fmt_green, fmt_red, fmt_yellow};
+use crate::styles::{fmt_green, fmt_red, fmt_yellow, ColorTheme};
+
+/// Whether stdout carries command output (e.g. `sdk create --output -`)
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// Routes spinners to stderr so stdout only carries the command output
+pub fn reserve_stdout() {
+    STDOUT_RESERVED.store(true, Ordering::Relaxed);
+}
 
 /// Wrapper around spinoff::Spinner to handle only
 /// showing if log level is INFO
@@ -19,8 +30,19 @@ impl Spinner {
             // level debug or quiet mode
             info!("{}...", msg.into());
             None
+        } else if STDOUT_RESERVED.load(Ordering::Relaxed) {
+            Some(spinoff::Spinner::new_with_stream(
+                spin_type,
+                msg,
+                ColorTheme::current().spinner_color(),
+                spinoff::Streams::Stderr,
+            ))
         } else {
-            Some(spinoff::Spinner::new(spin_type, msg, spinoff::Color::Cyan))
+            Some(spinoff::Spinner::new(
+                spin_type,
+                msg,
+                ColorTheme::current().spinner_color(),
+            ))
         };
 
         Self { sp }
@@ -36,7 +58,7 @@ impl Spinner {
 
     pub fn stop_success<M: Into<Cow<'static, str>>>(&mut self, msg: M) {
         let symbol = fmt_green("✔");
-        if let Some(sp) = self.sp.as_mut() {
+        if let Some(mut sp) = self.sp.take() {
             sp.stop_and_persist(&symbol, &msg.into());
         } else {
             info!("{symbol} {}", msg.into());
@@ -45,7 +67,7 @@ impl Spinner {
 
     pub fn stop_warn<M: Into<Cow<'static, str>>>(&mut self, msg: M) {
         let symbol = fmt_yellow("ø");
-        if let Some(sp) = self.sp.as_mut() {
+        if let Some(mut sp) = self.sp.take() {
             sp.stop_and_persist(&symbol, &msg.into());
         } else {
             warn!("{symbol} {}", msg.into());
@@ -54,10 +76,20 @@ impl Spinner {
 
     pub fn stop_error<M: Into<Cow<'static, str>>>(&mut self, msg: M) {
         let symbol = fmt_red("✘");
-        if let Some(sp) = self.sp.as_mut() {
+        if let Some(mut sp) = self.sp.take() {
             sp.stop_and_persist(&symbol, &msg.into());
         } else {
             error!("{symbol} {}", msg.into());
         }
     }
 }
+
+impl Drop for Spinner {
+    /// Clears a spinner that was never stopped (e.g. on error or interrupt) so
+    /// the terminal is not left with a stale spinner line
+    fn drop(&mut self) {
+        if let Some(mut sp) = self.sp.take() {
+            sp.clear();
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::styles::ColorTheme;
 use tabled::{
     settings::{object::Rows, themes::Colorization, Alignment, Color, Padding, Panel, Remove},
     Table,
@@ -6,12 +7,24 @@ use tabled::{
 pub fn header_panel(table: &mut Table, text: &str) {
     table
         .with(Panel::header(text))
-        .with(Colorization::exact(
-            [Color::BOLD | Color::BG_WHITE | Color::FG_BLACK],
-            Rows::first(),
-        ))
         .modify(Rows::first(), Padding::new(0, 0, 1, 1))
         .modify(Rows::first(), Alignment::center());
+
+    let header_color = match ColorTheme::current() {
+        ColorTheme::Default => Some(Color::BOLD | Color::BG_WHITE | Color::FG_BLACK),
+        ColorTheme::HighContrast => Some(Color::BOLD | Color::BG_BRIGHT_WHITE | Color::FG_BLACK),
+        ColorTheme::Mono => None,
+    };
+    if let Some(header_color) = header_color {
+        table.with(Colorization::exact([header_color], Rows::first()));
+    }
+}
+
+/// Emphasizes the table row at `index` (e.g. the column headers below a header panel)
+pub fn bold_row(table: &mut Table, index: usize) {
+    if ColorTheme::current() != ColorTheme::Mono {
+        table.modify(Rows::single(index), Color::BOLD);
+    }
 }
 
 pub fn preview_table(header: &str, text: &str, line_limit: usize) -> Table {
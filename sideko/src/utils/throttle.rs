@@ -0,0 +1,72 @@
+use std::{cell::Cell, future::Future, sync::OnceLock};
+
+use log::warn;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use super::config::ConfigKey;
+
+/// Default cap on concurrent requests to the Sideko API
+pub const DEFAULT_MAX_PARALLEL_REQUESTS: usize = 4;
+
+static REQUEST_SEMAPHORE: OnceLock<Semaphore> = OnceLock::new();
+
+tokio::task_local! {
+    /// Whether the current request flow holds a slot, see `flow`
+    static FLOW_HOLDS_SLOT: Cell<bool>;
+}
+
+/// Max concurrent requests configured via `--max-parallel-requests` (or its env var),
+/// falling back to the default
+pub fn max_parallel_requests() -> usize {
+    let key = ConfigKey::MaxParallelRequests;
+    key.get_env()
+        .and_then(|v| match v.trim().parse::<usize>() {
+            Ok(max) if max > 0 => Some(max),
+            _ => {
+                warn!("Ignoring invalid {key} value `{v}`, expected a positive integer");
+                None
+            }
+        })
+        .unwrap_or(DEFAULT_MAX_PARALLEL_REQUESTS)
+}
+
+/// Runs `flow` with its requests sharing a single slot of the request limit, so
+/// a flow holding a client (see `get_sideko_client`) can make nested requests
+/// without waiting on itself. The requests of a flow must be sent one at a time
+pub async fn flow<F: Future>(flow: F) -> F::Output {
+    FLOW_HOLDS_SLOT.scope(Cell::new(false), flow).await
+}
+
+/// Slot of the request limit, released when dropped
+pub struct Permit {
+    /// `None` when the request flow already holds a slot
+    permit: Option<SemaphorePermit<'static>>,
+}
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if self.permit.is_some() {
+            let _ = FLOW_HOLDS_SLOT.try_with(|holds| holds.set(false));
+        }
+    }
+}
+
+/// Waits for a slot in the process-wide request limit, the request may be sent
+/// while the returned permit is held
+///
+/// The limit is read once on first use, so it must be configured before any
+/// request is made.
+pub async fn acquire() -> Permit {
+    if FLOW_HOLDS_SLOT.try_with(Cell::get).unwrap_or(false) {
+        return Permit { permit: None };
+    }
+
+    let permit = REQUEST_SEMAPHORE
+        .get_or_init(|| Semaphore::new(max_parallel_requests()))
+        .acquire()
+        .await
+        .expect("request semaphore is never closed");
+    let _ = FLOW_HOLDS_SLOT.try_with(|holds| holds.set(true));
+    Permit {
+        permit: Some(permit),
+    }
+}
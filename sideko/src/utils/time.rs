@@ -0,0 +1,62 @@
+use chrono::{DateTime, Local, Utc};
+
+/// How timestamps are rendered in tables, JSON output always keeps the raw value
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct TimestampArgs {
+    /// Show timestamps in UTC
+    #[arg(long, conflicts_with = "local")]
+    pub utc: bool,
+
+    /// Show timestamps in the local timezone (default)
+    #[arg(long)]
+    pub local: bool,
+
+    /// Show timestamps relative to now (e.g. "3 days ago")
+    #[arg(long)]
+    pub relative: bool,
+}
+impl TimestampArgs {
+    /// Renders an RFC 3339 timestamp from the API, the raw value is
+    /// returned unchanged if it cannot be parsed
+    pub fn format(&self, raw: &str) -> String {
+        let Ok(parsed) = DateTime::parse_from_rfc3339(raw) else {
+            return raw.to_string();
+        };
+        let parsed = parsed.with_timezone(&Utc);
+
+        if self.relative {
+            relative(parsed, Utc::now())
+        } else if self.utc {
+            parsed.format("%Y-%m-%d %H:%M UTC").to_string()
+        } else {
+            parsed
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M %Z")
+                .to_string()
+        }
+    }
+}
+
+/// Human friendly distance between `time` and `now`, e.g. "3 days ago" or "in 2 hours"
+fn relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let delta = now.signed_duration_since(time);
+    let secs = delta.num_seconds().abs();
+    if secs < 60 {
+        return "just now".to_string();
+    }
+
+    let (count, unit) = match secs {
+        s if s < 3_600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3_600, "hour"),
+        s if s < 30 * 86_400 => (s / 86_400, "day"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+        s => (s / (365 * 86_400), "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+
+    if delta.num_seconds() >= 0 {
+        format!("{count} {unit}{plural} ago")
+    } else {
+        format!("in {count} {unit}{plural}")
+    }
+}
@@ -1,6 +1,50 @@
 use std::str::FromStr;
 
 use camino::Utf8PathBuf;
+use regex::Regex;
+use sideko_rest_api::models::SdkLanguageEnum;
+use url::Url;
+
+use super::{api_version, sdk_config::ConfigArg};
+
+// ------------- PROFILE VALIDATORS -------------
+/// Validates the config profile name only uses letters, digits, and `_`, so it
+/// can prefix dotenv keys and name keyring entries. `-` is rejected as dotenv keys
+/// can't contain it, and rewriting it would give `my-env` and `my_env` the same keys
+pub(crate) fn validate_profile_name(arg: &str) -> Result<String, String> {
+    let valid = !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(arg.to_string())
+    } else if arg.contains('-') {
+        Err(format!(
+            "Invalid profile name `{arg}`, `-` is not allowed, use `_` instead (e.g. `{}`)",
+            arg.replace('-', "_")
+        ))
+    } else {
+        Err(format!(
+            "Invalid profile name `{arg}`, only letters, digits, and `_` are allowed"
+        ))
+    }
+}
+
+// ------------- URL VALIDATORS -------------
+/// Validates the arg is a well-formed `http(s)://` URL
+pub(crate) fn validate_url(arg: &str) -> Result<Url, String> {
+    let url = Url::parse(arg.trim()).map_err(|e| format!("Invalid URL `{arg}`: {e}"))?;
+    if matches!(url.scheme(), "http" | "https") {
+        Ok(url)
+    } else {
+        Err(format!(
+            "Invalid URL `{arg}`, only http and https URLs are supported"
+        ))
+    }
+}
+
+// ------------- PATTERN VALIDATORS -------------
+/// Validates the arg is a well-formed glob pattern
+pub(crate) fn validate_glob(arg: &str) -> Result<glob::Pattern, String> {
+    glob::Pattern::new(arg).map_err(|e| format!("Invalid glob pattern `{arg}`: {e}"))
+}
 
 // ------------- PATH VALIDATORS -------------
 #[derive(Clone, Debug)]
@@ -49,8 +93,13 @@ pub fn validate_file_with_extension(
     extensions: &[&str],
 ) -> Result<Utf8PathBuf, String> {
     let path = validate_path(raw_path, PathKind::File, allow_dne)?;
+    // extensions are case-insensitive (e.g. `openapi.JSON`) on Windows and
+    // case-preserving filesystems
     let extension = format!(".{}", path.extension().unwrap_or_default());
-    if !extensions.contains(&extension.as_str()) {
+    if !extensions
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(&extension))
+    {
         Err(format!(
             "Path has incorrect extension, only {extensions:?} are permitted"
         ))
@@ -59,6 +108,12 @@ pub fn validate_file_with_extension(
     }
 }
 
+/// Validates path exists, either as a file or a directory
+pub(crate) fn validate_path_exists(arg: &str) -> Result<Utf8PathBuf, String> {
+    validate_path(arg, PathKind::File, false)
+        .or_else(|_| validate_path(arg, PathKind::Dir, false))
+        .map_err(|_| format!("Path `{arg}` must be an existing file or directory"))
+}
 /// Validates path exists and is a file
 pub(crate) fn validate_file(arg: &str) -> Result<Utf8PathBuf, String> {
     validate_path(arg, PathKind::File, false)
@@ -71,12 +126,56 @@ pub(crate) fn validate_file_json_yaml(arg: &str) -> Result<Utf8PathBuf, String>
 pub(crate) fn validate_file_yaml(arg: &str) -> Result<Utf8PathBuf, String> {
     validate_file_with_extension(arg, false, &[".yml", ".yaml"])
 }
+/// Validates path is an existing directory or a yaml file
+pub(crate) fn validate_file_yaml_or_dir(arg: &str) -> Result<Utf8PathBuf, String> {
+    validate_path(arg, PathKind::Dir, false).or_else(|_| validate_file_yaml(arg))
+}
+/// Validates the arg is `-` (stdin) or an existing yaml file
+pub(crate) fn validate_file_yaml_or_stdin(arg: &str) -> Result<Utf8PathBuf, String> {
+    if arg == super::sdk_config::STDIN_CONFIG {
+        Ok(Utf8PathBuf::from(arg))
+    } else {
+        validate_file_yaml(arg)
+    }
+}
+/// Validates the arg is `-` (stdin), an existing directory, or a yaml file
+pub(crate) fn validate_file_yaml_dir_or_stdin(arg: &str) -> Result<Utf8PathBuf, String> {
+    if arg == super::sdk_config::STDIN_CONFIG {
+        Ok(Utf8PathBuf::from(arg))
+    } else {
+        validate_file_yaml_or_dir(arg)
+    }
+}
+/// Validates the `--config` arg is an `http(s)://` URL, `-` (stdin), an existing
+/// directory, or a yaml file
+pub(crate) fn validate_config_source(arg: &str) -> Result<ConfigArg, String> {
+    match parse_config_url(arg)? {
+        Some(url) => Ok(ConfigArg::Url(url)),
+        None => validate_file_yaml_dir_or_stdin(arg).map(ConfigArg::Path),
+    }
+}
+/// Validates the `--config` arg is an `http(s)://` URL, `-` (stdin), or an existing yaml file
+pub(crate) fn validate_config_file_source(arg: &str) -> Result<ConfigArg, String> {
+    match parse_config_url(arg)? {
+        Some(url) => Ok(ConfigArg::Url(url)),
+        None if arg == super::sdk_config::STDIN_CONFIG => {
+            Ok(ConfigArg::Path(Utf8PathBuf::from(arg)))
+        }
+        None => validate_file_yaml(arg).map(ConfigArg::Path),
+    }
+}
+fn parse_config_url(arg: &str) -> Result<Option<Url>, String> {
+    let lower = arg.to_lowercase();
+    if !lower.starts_with("http://") && !lower.starts_with("https://") {
+        return Ok(None);
+    }
+    validate_url(arg).map(Some)
+}
 /// Validates yaml extension (does not exist is allowed)
 pub(crate) fn validate_file_yaml_allow_dne(arg: &str) -> Result<Utf8PathBuf, String> {
     validate_file_with_extension(arg, true, &[".yml", ".yaml"])
 }
 
-#[allow(unused)]
 /// Validates path is a file or does not exist
 pub(crate) fn validate_file_allow_dne(arg: &str) -> Result<Utf8PathBuf, String> {
     validate_path(arg, PathKind::File, true)
@@ -89,3 +188,187 @@ pub(crate) fn validate_dir(arg: &str) -> Result<Utf8PathBuf, String> {
 pub(crate) fn validate_dir_allow_dne(arg: &str) -> Result<Utf8PathBuf, String> {
     validate_path(arg, PathKind::Dir, true)
 }
+
+/// Validates the name is a legal filename on all major platforms
+pub(crate) fn validate_filename(name: &str) -> Result<(), String> {
+    const RESERVED_CHARS: [char; 9] = ['/', '\\', '<', '>', ':', '"', '|', '?', '*'];
+
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(format!("`{name}` is not a valid filename"));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| RESERVED_CHARS.contains(c) || c.is_control())
+    {
+        return Err(format!(
+            "Filename `{name}` contains the invalid character {c:?}"
+        ));
+    }
+    if name.ends_with(['.', ' ']) {
+        return Err(format!(
+            "Filename `{name}` must not end with a period or space"
+        ));
+    }
+    if name.len() > 255 {
+        return Err(format!("Filename `{name}` is longer than 255 bytes"));
+    }
+
+    Ok(())
+}
+
+// ------------- VERSION VALIDATORS -------------
+/// Validates the arg is a semantic version (e.g. `--version` of `sdk create`)
+pub(crate) fn validate_semver(arg: &str) -> Result<semver::Version, String> {
+    semver::Version::parse(arg.trim())
+        .map_err(|e| format!("Expected a semantic version like `1.2.3`, got `{arg}` ({e})"))
+}
+
+/// Validates the API version is `latest`, `auto`, or a semantic version, reading
+/// the version from a file when prefixed with `@` (e.g. `@api-version.txt`)
+pub(crate) fn validate_api_version(arg: &str) -> Result<String, String> {
+    let version = if let Some(path) = arg.strip_prefix('@') {
+        let path = validate_file(path)?;
+        std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed reading API version from `{path}`: {e}"))?
+            .trim()
+            .to_string()
+    } else {
+        arg.to_string()
+    };
+
+    if version == "latest"
+        || version == api_version::AUTO
+        || semver::Version::parse(&version).is_ok()
+    {
+        Ok(version)
+    } else {
+        Err(format!(
+            "API version `{version}` must be `latest`, `auto`, or a semantic version (e.g. `2.1.5`)"
+        ))
+    }
+}
+
+// ------------- SDK VERSION VALIDATORS -------------
+/// Version bumps accepted in place of an SDK version by `sdk update --version`
+const VERSION_BUMPS: [&str; 4] = ["patch", "minor", "major", "rc"];
+
+/// Validates the SDK version is a semantic version or a version bump
+pub(crate) fn validate_version_or_bump(arg: &str) -> Result<String, String> {
+    let version = arg.trim().to_lowercase();
+    if VERSION_BUMPS.contains(&version.as_str()) || semver::Version::parse(&version).is_ok() {
+        Ok(version)
+    } else {
+        Err(format!(
+            "SDK version `{arg}` must be a semantic version (e.g. `2.1.5`) or a version bump ({})",
+            VERSION_BUMPS.join(", ")
+        ))
+    }
+}
+
+// ------------- SDK NAME VALIDATORS -------------
+/// Validates the package name follows the naming rules of the language's package registry
+pub(crate) fn validate_package_name(lang: &SdkLanguageEnum, name: &str) -> Result<(), String> {
+    let (pattern, rule) = match lang {
+        SdkLanguageEnum::Python => (
+            r"^[A-Za-z0-9]([A-Za-z0-9._-]*[A-Za-z0-9])?$",
+            "letters, digits, `.`, `_`, and `-`, starting and ending with a letter or digit",
+        ),
+        SdkLanguageEnum::Typescript => (
+            r"^[a-z0-9][a-z0-9._-]{0,213}$",
+            "lower-case letters, digits, `.`, `_`, and `-` (max 214 characters)",
+        ),
+        SdkLanguageEnum::Rust => (
+            r"^[A-Za-z][A-Za-z0-9_-]{0,63}$",
+            "letters, digits, `_`, and `-`, starting with a letter (max 64 characters)",
+        ),
+        SdkLanguageEnum::Go => (
+            r"^[a-z][a-z0-9]*$",
+            "lower-case letters and digits, starting with a letter",
+        ),
+        SdkLanguageEnum::Java => (
+            r"^[a-z][a-z0-9_]*(\.[a-z][a-z0-9_]*)*$",
+            "dot-separated lower-case segments (e.g. `com.example.sdk`)",
+        ),
+    };
+
+    if Regex::new(pattern)
+        .expect("invalid package name regex pattern")
+        .is_match(name)
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid {lang} package name `{name}`, must contain only {rule}"
+        ))
+    }
+}
+
+/// Validates a Go module path (e.g. `github.com/my-org/my-sdk`)
+pub(crate) fn validate_go_module_path(path: &str) -> Result<(), String> {
+    let pattern = Regex::new(r"^[a-z0-9][a-z0-9.-]*\.[a-z]+(/[A-Za-z0-9._~-]+)+$")
+        .expect("invalid go module path regex pattern");
+    if pattern.is_match(path) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid Go module path `{path}`, must be a domain followed by a path (e.g. `github.com/my-org/my-sdk`)"
+        ))
+    }
+}
+
+/// Validates an npm scope, returning it with the leading `@`
+pub(crate) fn validate_npm_scope(scope: &str) -> Result<String, String> {
+    let scope = scope.strip_prefix('@').unwrap_or(scope);
+    let pattern = Regex::new(r"^[a-z0-9][a-z0-9._-]*$").expect("invalid npm scope regex pattern");
+    if pattern.is_match(scope) {
+        Ok(format!("@{scope}"))
+    } else {
+        Err(format!(
+            "Invalid npm scope `@{scope}`, must contain only lower-case letters, digits, `.`, `_`, and `-`"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// Path of an empty file named `name` in `dir`
+    fn touch(dir: &tempfile::TempDir, name: &str) -> String {
+        let path = dir.path().join(name);
+        fs::write(&path, "").unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn extensions_match_case_insensitively() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(validate_file_json_yaml(&touch(&dir, "openapi.JSON")).is_ok());
+        assert!(validate_file_json_yaml(&touch(&dir, "openapi.Yaml")).is_ok());
+        assert!(validate_file_yaml(&touch(&dir, "sdk-config.yml")).is_ok());
+        assert!(validate_file_yaml(&touch(&dir, "sdk-config.YML")).is_ok());
+    }
+
+    #[test]
+    fn other_extensions_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(validate_file_yaml(&touch(&dir, "openapi.JSON")).is_err());
+        assert!(validate_file_json_yaml(&touch(&dir, "openapi.txt")).is_err());
+        assert!(validate_file_json_yaml(&touch(&dir, "openapi")).is_err());
+    }
+
+    #[test]
+    fn profile_names_cannot_contain_dashes() {
+        assert!(validate_profile_name("my_env").is_ok());
+        assert!(validate_profile_name("Staging2").is_ok());
+
+        let err = validate_profile_name("my-env").unwrap_err();
+        assert!(err.contains("`my_env`"), "{err}");
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("my env").is_err());
+    }
+}